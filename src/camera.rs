@@ -1,11 +1,15 @@
 use core::f64;
 
 use glam::{dvec3, DVec3};
-use image::{Rgb, RgbImage};
+use image::{Rgb, Rgb32FImage, RgbImage};
 
-use crate::hittable::{Hittable, HittableList};
-use crate::random::{random_on_disc, random_square};
+use crate::hittable::{Hit, Hittable};
+use crate::material::SurfaceBsdf;
+use crate::random::{random_cube, random_on_disc, random_square};
 use crate::ray::Ray;
+use crate::scene::Scene;
+use crate::tonemap::{DisplayTransform, ToneMap, Transfer};
+use crate::vec::approx_zero;
 
 #[derive(Debug)]
 pub struct Camera {
@@ -26,25 +30,100 @@ pub struct Camera {
     // Ray bounce limit
     max_depth: u32,
 
-    // Gamma
-    gamma: f64,
+    // Tone mapping, exposure and gamma
+    display: DisplayTransform,
+
+    // Rays are spread over this time interval for motion blur
+    shutter_open: f64,
+    shutter_close: f64,
 }
 
-fn linear_to_gamma(data: f64, gamma: f64) -> f64 {
-    if data > 0.0 {
-        data.powf(1.0 / gamma)
+// Weight for combining two sampling strategies (Veach's power heuristic)
+fn power_heuristic(pdf: f64, other: f64) -> f64 {
+    let a = pdf * pdf;
+    let b = other * other;
+
+    if a + b > 0.0 {
+        a / (a + b)
     } else {
         0.0
     }
 }
 
-fn to_rgb(data: DVec3, gamma: f64) -> Rgb<u8> {
-    Rgb(data
-        .to_array()
-        .map(|x| (linear_to_gamma(x, gamma).clamp(0.0, 0.999) * 256.0).floor() as u8))
+// Contribution of one light sample, weighted against BSDF sampling
+// incoming gives the light the sampling strategy is responsible for
+fn direct_light(
+    ray: &Ray,
+    hit: &Hit,
+    bsdf: &SurfaceBsdf,
+    direction: DVec3,
+    light_pdf: f64,
+    incoming: impl Fn(&Ray) -> DVec3,
+) -> DVec3 {
+    if light_pdf <= 0.0 {
+        return DVec3::ZERO;
+    }
+
+    let f = bsdf.eval(-ray.direction, direction);
+
+    if approx_zero(f) {
+        return DVec3::ZERO;
+    }
+
+    let bsdf_pdf = bsdf.pdf(-ray.direction, direction);
+    let shadow_ray = Ray::new(hit.point, direction).with_time(ray.time);
+
+    f * incoming(&shadow_ray) * power_heuristic(light_pdf, bsdf_pdf) / light_pdf
+}
+
+// Next event estimation: one sample towards the scene's lights
+fn sample_lights(scene: &Scene, ray: &Ray, hit: &Hit, bsdf: &SurfaceBsdf) -> DVec3 {
+    let Some(direction) = scene.lights.sample_direction(hit.point, ray.time) else {
+        return DVec3::ZERO;
+    };
+
+    let direction = direction.normalize();
+    let light_pdf = scene.lights.pdf(hit.point, direction, ray.time);
+
+    // Whatever is hit first is what is seen, so occlusion comes for free
+    direct_light(ray, hit, bsdf, direction, light_pdf, |shadow_ray| {
+        scene
+            .world
+            .hit(shadow_ray, 1e-9, f64::MAX)
+            .map_or(DVec3::ZERO, |light_hit| {
+                light_hit.material.emitted(shadow_ray, &light_hit)
+                    * scene.transmittance(shadow_ray, 1e-9, light_hit.distance)
+            })
+    })
+}
+
+// One sample towards the environment, e.g. the sun in an HDRI
+fn sample_environment(scene: &Scene, ray: &Ray, hit: &Hit, bsdf: &SurfaceBsdf) -> DVec3 {
+    let Some(direction) = scene.environment.sample_direction() else {
+        return DVec3::ZERO;
+    };
+
+    let light_pdf = scene.environment.pdf(direction);
+
+    direct_light(
+        ray,
+        hit,
+        bsdf,
+        direction,
+        light_pdf,
+        |shadow_ray| match scene.world.hit(shadow_ray, 1e-9, f64::MAX) {
+            Some(_) => DVec3::ZERO,
+            None => {
+                scene.environment.radiance(direction)
+                    * scene.transmittance(shadow_ray, 1e-9, f64::INFINITY)
+            }
+        },
+    )
 }
 
-fn sample(world: &HittableList, ray: &Ray, depth: u32) -> DVec3 {
+// bsdf_pdf is the pdf the previous bounce chose this ray with
+// None for camera rays and specular bounces that can't be light sampled
+fn sample(scene: &Scene, ray: &Ray, depth: u32, bsdf_pdf: Option<f64>) -> DVec3 {
     let ambient = DVec3::ZERO;
 
     // No light after depth exceeded
@@ -53,22 +132,41 @@ fn sample(world: &HittableList, ray: &Ray, depth: u32) -> DVec3 {
     }
 
     // Avoid intersecting same object by using a small value
-    if let Some(hit) = world.hit(ray, 1e-9, f64::MAX) {
-        if let Some((attenuation, potential_ray)) = hit.material.scatter(ray, &hit) {
-            if let Some(new_ray) = potential_ray {
-                return attenuation * sample(world, &new_ray, depth - 1);
-            }
-
-            return attenuation;
-        }
-
-        return ambient;
+    if let Some(hit) = scene.hit(ray, 1e-9, f64::MAX) {
+        // Light sampling could also have found this emission
+        let weight = bsdf_pdf.map_or(1.0, |pdf| {
+            power_heuristic(pdf, scene.lights.pdf(ray.origin, ray.direction, ray.time))
+        });
+        let emitted = weight * hit.material.emitted(ray, &hit);
+
+        let Some(bsdf) = hit.material.bsdf(&hit) else {
+            return emitted;
+        };
+
+        // Light found by the next bounce would be past the depth limit, and
+        // specular lobes can't be hit by light sampling
+        let direct = if bsdf.flags().is_non_specular() && depth > 1 {
+            sample_lights(scene, ray, &hit, &bsdf) + sample_environment(scene, ray, &hit, &bsdf)
+        } else {
+            DVec3::ZERO
+        };
+
+        let Some(bsdf_sample) = bsdf.sample(-ray.direction, random_cube()) else {
+            return emitted + direct;
+        };
+
+        let new_ray = Ray::new(hit.point, bsdf_sample.wi).with_time(ray.time);
+        let pdf = (!bsdf_sample.flags.is_specular()).then_some(bsdf_sample.pdf);
+
+        return emitted + direct + bsdf_sample.weight * sample(scene, &new_ray, depth - 1, pdf);
     }
 
-    // Background
-    let a = 0.5 * (ray.direction.y + 1.0);
-    DVec3::ONE.lerp(dvec3(0.5, 0.7, 1.0), a)
-    // DVec3::ZERO
+    // Environment sampling could also have found this direction
+    let weight = bsdf_pdf.map_or(1.0, |pdf| {
+        power_heuristic(pdf, scene.environment.pdf(ray.direction))
+    });
+
+    weight * scene.environment.radiance(ray.direction)
 }
 
 impl Camera {
@@ -85,6 +183,11 @@ impl Camera {
             samples_per_pixel,
             max_depth,
             gamma,
+            tone_map,
+            exposure,
+            transfer,
+            shutter_open,
+            shutter_close,
         }: &CameraParams,
     ) -> Self {
         let aspect_ratio = width as f64 / height as f64;
@@ -117,7 +220,14 @@ impl Camera {
             height,
             samples_per_pixel,
             max_depth,
-            gamma,
+            display: DisplayTransform {
+                tone_map,
+                exposure,
+                transfer,
+                gamma,
+            },
+            shutter_open,
+            shutter_close,
         }
     }
 
@@ -130,7 +240,8 @@ impl Camera {
     fn get_ray(&self, (u, v): (f64, f64)) -> Ray {
         let end = self.top_left + self.viewport_u * u + self.viewport_v * v;
         let start = self.sample_defocus_disk();
-        Ray::new(start, end - start)
+        let time = self.shutter_open + (self.shutter_close - self.shutter_open) * fastrand::f64();
+        Ray::new(start, end - start).with_time(time)
     }
 
     fn get_uv(&self, x: u32, y: u32) -> (f64, f64) {
@@ -141,23 +252,29 @@ impl Camera {
         )
     }
 
-    fn render_pixel(&self, world: &HittableList, x: u32, y: u32) -> Rgb<u8> {
+    fn render_pixel(&self, scene: &Scene, x: u32, y: u32) -> Rgb<f32> {
         let mut out = DVec3::ZERO;
 
         for _ in 0..self.samples_per_pixel {
             let uv = self.get_uv(x, y);
             let ray = self.get_ray(uv);
-            out += sample(world, &ray, self.max_depth);
+            out += sample(scene, &ray, self.max_depth, None);
         }
 
-        to_rgb(out / self.samples_per_pixel as f64, self.gamma)
+        Rgb((out / self.samples_per_pixel as f64).as_vec3().to_array())
     }
 
-    pub fn render(&self, world: &HittableList) -> RgbImage {
-        RgbImage::from_par_fn(self.width, self.height, |x, y| {
-            self.render_pixel(world, x, y)
+    // Linear radiance, see output for saving it without losing range
+    pub fn render(&self, scene: &Scene) -> Rgb32FImage {
+        Rgb32FImage::from_par_fn(self.width, self.height, |x, y| {
+            self.render_pixel(scene, x, y)
         })
     }
+
+    // Tone maps and quantises a render for display
+    pub fn develop(&self, image: &Rgb32FImage) -> RgbImage {
+        self.display.develop(image)
+    }
 }
 
 impl Default for Camera {
@@ -178,6 +295,13 @@ pub struct CameraParams {
     pub samples_per_pixel: u32,
     pub max_depth: u32,
     pub gamma: f64,
+    pub tone_map: ToneMap,
+    // In stops
+    pub exposure: f64,
+    pub transfer: Transfer,
+    // Same time units as object motion, equal for no motion blur
+    pub shutter_open: f64,
+    pub shutter_close: f64,
 }
 
 impl Default for CameraParams {
@@ -194,6 +318,11 @@ impl Default for CameraParams {
             samples_per_pixel: 16,
             max_depth: 64,
             gamma: 2.0,
+            tone_map: ToneMap::Clamp,
+            exposure: 0.0,
+            transfer: Transfer::Gamma,
+            shutter_open: 0.0,
+            shutter_close: 0.0,
         }
     }
 }
@@ -0,0 +1,192 @@
+use glam::{dvec2, DVec2};
+
+// Piecewise constant function over [0, 1] that can be sampled proportionally
+pub struct Distribution1D {
+    function: Vec<f64>,
+    cdf: Vec<f64>,
+    integral: f64,
+}
+
+impl Distribution1D {
+    // Negative values are treated as zero
+    pub fn new(function: Vec<f64>) -> Self {
+        assert!(!function.is_empty());
+
+        let n = function.len() as f64;
+        let function: Vec<f64> = function.into_iter().map(|x| x.max(0.0)).collect();
+
+        let mut cdf = Vec::with_capacity(function.len() + 1);
+        cdf.push(0.0);
+
+        for (i, value) in function.iter().enumerate() {
+            cdf.push(cdf[i] + value / n);
+        }
+
+        let integral = cdf[function.len()];
+
+        // All zero, fall back to uniform sampling
+        if integral > 0.0 {
+            cdf.iter_mut().for_each(|x| *x /= integral);
+        } else {
+            cdf.iter_mut()
+                .enumerate()
+                .for_each(|(i, x)| *x = i as f64 / n);
+        }
+
+        Distribution1D {
+            function,
+            cdf,
+            integral,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.function.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.function.is_empty()
+    }
+
+    pub fn integral(&self) -> f64 {
+        self.integral
+    }
+
+    // Returns the sample in [0, 1), its pdf and which piece it is in
+    pub fn sample(&self, u: f64) -> (f64, f64, usize) {
+        let offset = (self.cdf.partition_point(|&x| x <= u).max(1) - 1).min(self.len() - 1);
+
+        let width = self.cdf[offset + 1] - self.cdf[offset];
+        let du = if width > 0.0 {
+            (u - self.cdf[offset]) / width
+        } else {
+            0.0
+        };
+
+        let x = ((offset as f64 + du) / self.len() as f64).min(1.0 - f64::EPSILON);
+        (x, self.pdf_at(offset), offset)
+    }
+
+    pub fn pdf(&self, x: f64) -> f64 {
+        let offset = ((x * self.len() as f64) as usize).min(self.len() - 1);
+        self.pdf_at(offset)
+    }
+
+    fn pdf_at(&self, offset: usize) -> f64 {
+        if self.integral > 0.0 {
+            self.function[offset] / self.integral
+        } else {
+            1.0
+        }
+    }
+}
+
+// Piecewise constant function over [0, 1]^2 using marginal and conditional
+// distributions, rows are v and columns are u
+pub struct Distribution2D {
+    conditional: Vec<Distribution1D>,
+    marginal: Distribution1D,
+}
+
+impl Distribution2D {
+    // Values are in row major order
+    pub fn new(function: &[f64], width: usize, height: usize) -> Self {
+        assert_eq!(function.len(), width * height);
+
+        let conditional: Vec<Distribution1D> = function
+            .chunks(width)
+            .map(|row| Distribution1D::new(row.to_vec()))
+            .collect();
+
+        let marginal = Distribution1D::new(conditional.iter().map(|x| x.integral()).collect());
+
+        Distribution2D {
+            conditional,
+            marginal,
+        }
+    }
+
+    // Returns a point in [0, 1)^2 and its pdf with respect to area
+    pub fn sample(&self, u: DVec2) -> (DVec2, f64) {
+        let (v, pdf_v, row) = self.marginal.sample(u.y);
+        let (u, pdf_u, _) = self.conditional[row].sample(u.x);
+        (dvec2(u, v), pdf_u * pdf_v)
+    }
+
+    pub fn pdf(&self, uv: DVec2) -> f64 {
+        if self.marginal.integral() <= 0.0 {
+            return 1.0;
+        }
+
+        let height = self.conditional.len();
+        let row = ((uv.y * height as f64) as usize).min(height - 1);
+        let conditional = &self.conditional[row];
+        let column = ((uv.x * conditional.len() as f64) as usize).min(conditional.len() - 1);
+
+        conditional.function[column] / self.marginal.integral()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sample_proportionally() {
+        let distribution = Distribution1D::new(vec![1.0, 3.0]);
+
+        assert_eq!(distribution.integral(), 2.0);
+
+        // First quarter of u maps onto the first half of x
+        let (x, pdf, offset) = distribution.sample(0.125);
+        assert!((x - 0.25).abs() < 1e-9);
+        assert!((pdf - 0.5).abs() < 1e-9);
+        assert_eq!(offset, 0);
+
+        let (x, pdf, offset) = distribution.sample(0.625);
+        assert!((x - 0.75).abs() < 1e-9);
+        assert!((pdf - 1.5).abs() < 1e-9);
+        assert_eq!(offset, 1);
+    }
+
+    #[test]
+    fn zero_function_is_uniform() {
+        let distribution = Distribution1D::new(vec![0.0; 4]);
+        let (x, pdf, _) = distribution.sample(0.3);
+
+        assert!((x - 0.3).abs() < 1e-9);
+        assert_eq!(pdf, 1.0);
+    }
+
+    #[test]
+    fn pdf_matches_sample() {
+        let function = [0.0, 1.0, 2.0, 0.5, 4.0, 1.0];
+        let distribution = Distribution2D::new(&function, 3, 2);
+
+        for i in 0..50 {
+            let u = dvec2((i as f64 * 0.618).fract(), (i as f64 * 0.382).fract());
+            let (uv, pdf) = distribution.sample(u);
+
+            assert!((distribution.pdf(uv) - pdf).abs() < 1e-9);
+            assert!(pdf > 0.0);
+        }
+    }
+
+    #[test]
+    fn pdf_integrates_to_one() {
+        let function = [0.0, 1.0, 2.0, 0.5, 4.0, 1.0];
+        let distribution = Distribution2D::new(&function, 3, 2);
+
+        let n = 60;
+        let mut sum = 0.0;
+
+        for i in 0..n {
+            for j in 0..n {
+                let uv = dvec2((i as f64 + 0.5) / n as f64, (j as f64 + 0.5) / n as f64);
+                sum += distribution.pdf(uv);
+            }
+        }
+
+        assert!((sum / (n * n) as f64 - 1.0).abs() < 1e-9);
+    }
+}
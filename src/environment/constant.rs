@@ -0,0 +1,24 @@
+use glam::DVec3;
+
+use super::traits::Environment;
+
+pub struct Constant {
+    colour: DVec3,
+}
+
+impl Constant {
+    pub fn new(colour: DVec3) -> Self {
+        Constant { colour }
+    }
+
+    // For enclosed scenes lit only by emissive objects
+    pub fn black() -> Self {
+        Constant::new(DVec3::ZERO)
+    }
+}
+
+impl Environment for Constant {
+    fn radiance(&self, _direction: DVec3) -> DVec3 {
+        self.colour
+    }
+}
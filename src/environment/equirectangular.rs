@@ -0,0 +1,169 @@
+use std::f64::consts::{PI, TAU};
+use std::path::Path;
+
+use glam::{dvec2, dvec3, DQuat, DVec2, DVec3};
+use image::{ImageResult, Rgb32FImage};
+
+use super::traits::Environment;
+use crate::distribution::Distribution2D;
+
+// Maps a unit direction to [0, 1]^2 with +y at v = 0 and -z at u = 0.5
+fn direction_to_uv(direction: DVec3) -> DVec2 {
+    let u = 0.5 + direction.x.atan2(-direction.z) / TAU;
+    let v = direction.y.clamp(-1.0, 1.0).acos() / PI;
+    dvec2(u, v)
+}
+
+fn uv_to_direction(uv: DVec2) -> DVec3 {
+    let phi = (uv.x - 0.5) * TAU;
+    let theta = uv.y * PI;
+    dvec3(
+        theta.sin() * phi.sin(),
+        theta.cos(),
+        -theta.sin() * phi.cos(),
+    )
+}
+
+// Pixels are weighted by luminance and by the solid angle they cover
+fn build_distribution(image: &Rgb32FImage) -> Distribution2D {
+    let (width, height) = image.dimensions();
+
+    let function: Vec<f64> = image
+        .enumerate_pixels()
+        .map(|(_, y, pixel)| {
+            let [r, g, b] = pixel.0.map(|x| x as f64);
+            let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+            let sin_theta = (PI * (y as f64 + 0.5) / height as f64).sin();
+            luminance * sin_theta
+        })
+        .collect();
+
+    Distribution2D::new(&function, width as usize, height as usize)
+}
+
+// Latitude-longitude environment map, usually loaded from an HDR file
+pub struct Equirectangular {
+    image: Rgb32FImage,
+    rotation: DQuat,
+    intensity: f64,
+
+    // For importance sampling bright regions such as the sun
+    distribution: Distribution2D,
+}
+
+impl Equirectangular {
+    pub fn new(image: Rgb32FImage) -> Self {
+        Equirectangular {
+            distribution: build_distribution(&image),
+            image,
+            rotation: DQuat::IDENTITY,
+            intensity: 1.0,
+        }
+    }
+
+    // Any format the image crate reads, .hdr and .exr keep their full range
+    pub fn load(path: impl AsRef<Path>) -> ImageResult<Self> {
+        Ok(Equirectangular::new(image::open(path)?.into_rgb32f()))
+    }
+
+    // Rotates the map in world space
+    pub fn with_rotation(mut self, rotation: DQuat) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn with_intensity(mut self, intensity: f64) -> Self {
+        self.intensity = intensity;
+        self
+    }
+
+    fn to_local(&self, direction: DVec3) -> DVec3 {
+        self.rotation.inverse() * direction.normalize()
+    }
+
+    // Nearest pixel, so the map is piecewise constant
+    fn pixel(&self, uv: DVec2) -> (u32, u32) {
+        let (width, height) = self.image.dimensions();
+        let x = ((uv.x.rem_euclid(1.0) * width as f64) as u32).min(width - 1);
+        let y = ((uv.y * height as f64) as u32).min(height - 1);
+        (x, y)
+    }
+
+    fn texel(&self, x: u32, y: u32) -> DVec3 {
+        let [r, g, b] = self.image.get_pixel(x, y).0;
+        dvec3(r as f64, g as f64, b as f64) * self.intensity
+    }
+}
+
+impl Environment for Equirectangular {
+    fn radiance(&self, direction: DVec3) -> DVec3 {
+        let (x, y) = self.pixel(direction_to_uv(self.to_local(direction)));
+        self.texel(x, y)
+    }
+
+    fn pdf(&self, direction: DVec3) -> f64 {
+        let uv = direction_to_uv(self.to_local(direction));
+        let sin_theta = (uv.y * PI).sin();
+
+        if sin_theta <= 0.0 {
+            return 0.0;
+        }
+
+        // Jacobian from the unit square to the sphere
+        self.distribution.pdf(uv) / (2.0 * PI * PI * sin_theta)
+    }
+
+    fn sample_direction(&self) -> Option<DVec3> {
+        let (uv, pdf) = self
+            .distribution
+            .sample(dvec2(fastrand::f64(), fastrand::f64()));
+
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(self.rotation * uv_to_direction(uv))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgb;
+
+    use super::*;
+    use crate::vec::approx_eq;
+
+    #[test]
+    fn uv_round_trip() {
+        for direction in [
+            dvec3(1.0, 0.0, 0.0),
+            dvec3(0.0, 0.0, 1.0),
+            dvec3(-0.3, 0.5, -0.8).normalize(),
+            dvec3(0.2, -0.9, 0.1).normalize(),
+        ] {
+            let uv = direction_to_uv(direction);
+            assert!(approx_eq(uv_to_direction(uv), direction));
+        }
+    }
+
+    #[test]
+    fn samples_bright_pixel() {
+        let mut image = Rgb32FImage::from_pixel(16, 8, Rgb([0.01, 0.01, 0.01]));
+        image.put_pixel(5, 3, Rgb([1000.0, 1000.0, 1000.0]));
+
+        let map = Equirectangular::new(image).with_rotation(DQuat::from_rotation_y(1.0));
+        let bright = (0..100)
+            .filter_map(|_| map.sample_direction())
+            .filter(|&direction| map.radiance(direction).x > 1.0)
+            .count();
+
+        assert!(bright > 90);
+    }
+
+    #[test]
+    fn poles_and_forward() {
+        assert!(direction_to_uv(dvec3(0.0, 1.0, 0.0)).y.abs() < 1e-9);
+        assert!((direction_to_uv(dvec3(0.0, -1.0, 0.0)).y - 1.0).abs() < 1e-9);
+        assert!((direction_to_uv(dvec3(0.0, 0.0, -1.0)) - dvec2(0.5, 0.5)).length() < 1e-9);
+    }
+}
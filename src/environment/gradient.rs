@@ -0,0 +1,33 @@
+use glam::{dvec3, DVec3};
+
+use super::traits::Environment;
+
+// Vertical blend from bottom (straight down) to top (straight up)
+pub struct Gradient {
+    bottom: DVec3,
+    top: DVec3,
+}
+
+impl Gradient {
+    pub fn new(bottom: DVec3, top: DVec3) -> Self {
+        Gradient { bottom, top }
+    }
+
+    // White to light blue
+    pub fn sky() -> Self {
+        Gradient::new(DVec3::ONE, dvec3(0.5, 0.7, 1.0))
+    }
+}
+
+impl Default for Gradient {
+    fn default() -> Self {
+        Gradient::sky()
+    }
+}
+
+impl Environment for Gradient {
+    fn radiance(&self, direction: DVec3) -> DVec3 {
+        let a = 0.5 * (direction.normalize().y + 1.0);
+        self.bottom.lerp(self.top, a)
+    }
+}
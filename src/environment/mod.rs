@@ -0,0 +1,9 @@
+pub mod constant;
+pub mod equirectangular;
+pub mod gradient;
+pub mod traits;
+
+pub use constant::Constant;
+pub use equirectangular::Equirectangular;
+pub use gradient::Gradient;
+pub use traits::Environment;
@@ -0,0 +1,17 @@
+use glam::DVec3;
+
+pub trait Environment: Send + Sync {
+    // Radiance arriving from direction for rays that miss everything
+    fn radiance(&self, direction: DVec3) -> DVec3;
+
+    // Solid angle pdf of sample_direction choosing direction
+    fn pdf(&self, _direction: DVec3) -> f64 {
+        0.0
+    }
+
+    // Unit direction chosen roughly in proportion to radiance
+    // None if the environment isn't worth sampling directly
+    fn sample_direction(&self) -> Option<DVec3> {
+        None
+    }
+}
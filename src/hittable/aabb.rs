@@ -0,0 +1,212 @@
+use glam::{BVec3, DAffine3, DVec3};
+
+use crate::ray::Ray;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: DVec3,
+    pub max: DVec3,
+}
+
+impl Aabb {
+    // Union with anything gives the other box
+    pub const EMPTY: Self = Self {
+        min: DVec3::INFINITY,
+        max: DVec3::NEG_INFINITY,
+    };
+
+    // For objects with no finite bounds (e.g. planes)
+    pub const INFINITE: Self = Self {
+        min: DVec3::NEG_INFINITY,
+        max: DVec3::INFINITY,
+    };
+
+    // Corners can be given in any order
+    pub fn new(a: DVec3, b: DVec3) -> Self {
+        Self {
+            min: a.min(b),
+            max: a.max(b),
+        }
+    }
+
+    pub fn from_points(points: impl IntoIterator<Item = DVec3>) -> Self {
+        points
+            .into_iter()
+            .fold(Self::EMPTY, |acc, point| acc.grow(point))
+    }
+
+    pub fn union(&self, other: &Aabb) -> Self {
+        Self {
+            min: self.min.min(other.min),
+            max: self.max.max(other.max),
+        }
+    }
+
+    // Empty if the boxes don't overlap
+    pub fn intersection(&self, other: &Aabb) -> Self {
+        Self {
+            min: self.min.max(other.min),
+            max: self.max.min(other.max),
+        }
+    }
+
+    pub fn grow(&self, point: DVec3) -> Self {
+        Self {
+            min: self.min.min(point),
+            max: self.max.max(point),
+        }
+    }
+
+    // Avoid zero thickness boxes for flat objects
+    pub fn pad(&self, delta: f64) -> Self {
+        Self {
+            min: self.min - delta,
+            max: self.max + delta,
+        }
+    }
+
+    // Box around all 8 transformed corners, empty and infinite boxes stay so
+    pub fn transform(&self, matrix: &DAffine3) -> Self {
+        if self.is_empty() {
+            return Self::EMPTY;
+        }
+
+        if !self.is_finite() {
+            return Self::INFINITE;
+        }
+
+        Self::from_points((0..8).map(|i| {
+            let corner = DVec3::select(
+                BVec3::new(i & 1 != 0, i & 2 != 0, i & 4 != 0),
+                self.max,
+                self.min,
+            );
+            matrix.transform_point3(corner)
+        }))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.min.cmpgt(self.max).any()
+    }
+
+    pub fn is_finite(&self) -> bool {
+        self.min.is_finite() && self.max.is_finite()
+    }
+
+    pub fn centroid(&self) -> DVec3 {
+        (self.min + self.max) / 2.0
+    }
+
+    pub fn extent(&self) -> DVec3 {
+        self.max - self.min
+    }
+
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+
+        let e = self.extent();
+        2.0 * (e.x * e.y + e.y * e.z + e.z * e.x)
+    }
+
+    pub fn longest_axis(&self) -> usize {
+        let e = self.extent();
+
+        if e.x > e.y && e.x > e.z {
+            0
+        } else if e.y > e.z {
+            1
+        } else {
+            2
+        }
+    }
+
+    // Slab test, inv_direction is passed in so it is only computed once per ray
+    pub fn hit_with_inverse(&self, ray: &Ray, inv_direction: DVec3, min: f64, max: f64) -> bool {
+        self.interval_with_inverse(ray, inv_direction, min, max)
+            .is_some()
+    }
+
+    pub fn hit(&self, ray: &Ray, min: f64, max: f64) -> bool {
+        self.hit_with_inverse(ray, ray.direction.recip(), min, max)
+    }
+
+    // Distances where the ray enters and leaves the box, clipped to min..max
+    pub fn interval(&self, ray: &Ray, min: f64, max: f64) -> Option<(f64, f64)> {
+        self.interval_with_inverse(ray, ray.direction.recip(), min, max)
+    }
+
+    fn interval_with_inverse(
+        &self,
+        ray: &Ray,
+        inv_direction: DVec3,
+        min: f64,
+        max: f64,
+    ) -> Option<(f64, f64)> {
+        let t0 = (self.min - ray.origin) * inv_direction;
+        let t1 = (self.max - ray.origin) * inv_direction;
+
+        // NaN from 0 * inf is dropped by f64::max/min
+        let near = t0.min(t1).max_element().max(min);
+        let far = t0.max(t1).min_element().min(max);
+
+        (near <= far).then_some((near, far))
+    }
+}
+
+impl Default for Aabb {
+    fn default() -> Self {
+        Self::EMPTY
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+    use crate::vec::approx_eq;
+
+    #[test]
+    fn union_with_empty() {
+        let a = Aabb::new(dvec3(1.0, 2.0, 3.0), dvec3(-1.0, 0.0, 5.0));
+
+        assert_eq!(a.union(&Aabb::EMPTY), a);
+        assert!(approx_eq(a.min, dvec3(-1.0, 0.0, 3.0)));
+        assert!(approx_eq(a.max, dvec3(1.0, 2.0, 5.0)));
+    }
+
+    #[test]
+    fn surface_area_of_cube() {
+        let a = Aabb::new(DVec3::ZERO, DVec3::splat(2.0));
+
+        assert_eq!(a.surface_area(), 24.0);
+        assert_eq!(Aabb::EMPTY.surface_area(), 0.0);
+    }
+
+    #[test]
+    fn transform_keeps_empty_and_infinite() {
+        let matrix = DAffine3::from_translation(dvec3(1.0, 2.0, 3.0));
+
+        assert!(Aabb::EMPTY.transform(&matrix).is_empty());
+        assert_eq!(Aabb::INFINITE.transform(&matrix), Aabb::INFINITE);
+    }
+
+    #[test]
+    fn ray_hits_box() {
+        let a = Aabb::new(DVec3::splat(-1.0), DVec3::ONE);
+        let ray = Ray::new(dvec3(-5.0, 0.5, 0.5), dvec3(1.0, 0.0, 0.0));
+
+        assert!(a.hit(&ray, 0.0, f64::MAX));
+        assert!(!a.hit(&ray, 0.0, 3.0));
+    }
+
+    #[test]
+    fn ray_misses_box() {
+        let a = Aabb::new(DVec3::splat(-1.0), DVec3::ONE);
+        let ray = Ray::new(dvec3(-5.0, 2.0, 0.0), dvec3(1.0, 0.0, 0.0));
+
+        assert!(!a.hit(&ray, 0.0, f64::MAX));
+    }
+}
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use glam::{dvec3, DVec3};
+
+use super::aabb::Aabb;
+use super::hit::Hit;
+use super::hittablelist::HittableList;
+use super::quad::Quad;
+use super::traits::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+
+// Axis aligned box made of six outward facing quads, rotate it with
+// Transformed. Closed, so it also works in CSG
+pub struct BoxShape {
+    bounds: Aabb,
+    sides: HittableList,
+}
+
+impl BoxShape {
+    // Corners can be given in any order
+    pub fn new(a: DVec3, b: DVec3, material: Arc<dyn Material>) -> Self {
+        let bounds = Aabb::new(a, b);
+        let (min, max) = (bounds.min, bounds.max);
+
+        let dx = dvec3(max.x - min.x, 0.0, 0.0);
+        let dy = dvec3(0.0, max.y - min.y, 0.0);
+        let dz = dvec3(0.0, 0.0, max.z - min.z);
+
+        let mut sides = HittableList::new();
+
+        // Front, right, back, left, top and bottom, u cross v points outwards
+        sides.add(Quad::new(
+            dvec3(min.x, min.y, max.z),
+            dx,
+            dy,
+            material.clone(),
+        ));
+        sides.add(Quad::new(
+            dvec3(max.x, min.y, max.z),
+            -dz,
+            dy,
+            material.clone(),
+        ));
+        sides.add(Quad::new(
+            dvec3(max.x, min.y, min.z),
+            -dx,
+            dy,
+            material.clone(),
+        ));
+        sides.add(Quad::new(
+            dvec3(min.x, min.y, min.z),
+            dz,
+            dy,
+            material.clone(),
+        ));
+        sides.add(Quad::new(
+            dvec3(min.x, max.y, max.z),
+            dx,
+            -dz,
+            material.clone(),
+        ));
+        sides.add(Quad::new(dvec3(min.x, min.y, min.z), dx, dz, material));
+
+        BoxShape { bounds, sides }
+    }
+}
+
+impl Hittable for BoxShape {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        self.sides.hit(ray, min, max)
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<(Hit, Hit)> {
+        let Some((entry, exit)) = self.bounds.interval(ray, f64::NEG_INFINITY, f64::INFINITY)
+        else {
+            return vec![];
+        };
+
+        // The slab test gives the distances, the sides give the rest
+        let side = |distance: f64| {
+            let error = 1e-9 * distance.abs().max(1.0);
+            self.sides.hit(ray, distance - error, distance + error)
+        };
+
+        match (side(entry), side(exit)) {
+            (Some(entry), Some(exit)) => vec![(entry, exit)],
+            _ => vec![],
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounds
+    }
+
+    fn pdf(&self, origin: DVec3, direction: DVec3, time: f64) -> f64 {
+        self.sides.pdf(origin, direction, time)
+    }
+
+    fn sample_direction(&self, origin: DVec3, time: f64) -> Option<DVec3> {
+        self.sides.sample_direction(origin, time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec::approx_eq;
+
+    fn unit_box() -> BoxShape {
+        BoxShape::new(
+            dvec3(1.0, 1.0, 1.0),
+            dvec3(-1.0, -1.0, -1.0),
+            Arc::new(Lambertian::new(DVec3::ONE)),
+        )
+    }
+
+    #[test]
+    fn outward_normals() {
+        for direction in [
+            DVec3::X,
+            DVec3::Y,
+            DVec3::Z,
+            -DVec3::X,
+            -DVec3::Y,
+            -DVec3::Z,
+        ] {
+            let ray = Ray::new(direction * 5.0, -direction);
+            let hit = unit_box().hit(&ray, 1e-9, f64::MAX).unwrap();
+
+            assert!((hit.distance - 4.0).abs() < 1e-9);
+            assert!(hit.front_face);
+            assert!(approx_eq(hit.normal, direction));
+        }
+    }
+
+    #[test]
+    fn intervals_cover_the_inside() {
+        let ray = Ray::new(dvec3(0.2, 0.3, 0.0), DVec3::Z);
+        let intervals = unit_box().intervals(&ray);
+
+        assert_eq!(intervals.len(), 1);
+        let (entry, exit) = &intervals[0];
+        assert!((entry.distance + 1.0).abs() < 1e-9);
+        assert!((exit.distance - 1.0).abs() < 1e-9);
+        assert!(approx_eq(exit.normal, -DVec3::Z));
+    }
+}
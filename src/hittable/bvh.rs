@@ -0,0 +1,377 @@
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use super::aabb::Aabb;
+use super::hit::Hit;
+use super::hittablelist::HittableList;
+use super::traits::Hittable;
+use crate::ray::Ray;
+
+const BINS: usize = 16;
+const MAX_LEAF_SIZE: usize = 4;
+
+// Relative costs used by the surface area heuristic
+const TRAVERSAL_COST: f64 = 1.0;
+const INTERSECTION_COST: f64 = 1.0;
+
+#[derive(Debug, Clone, Copy)]
+enum NodeKind {
+    // Objects in [start, start + count)
+    Leaf { start: usize, count: usize },
+    // First child is always the next node in the array
+    Interior { second: usize, axis: usize },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Node {
+    bounds: Aabb,
+    kind: NodeKind,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Bin {
+    bounds: Aabb,
+    count: usize,
+}
+
+struct BuildItem {
+    bounds: Aabb,
+    centroid: DVec3,
+    object: Arc<dyn Hittable>,
+}
+
+pub struct Bvh {
+    nodes: Vec<Node>,
+    objects: Vec<Arc<dyn Hittable>>,
+
+    // Objects without finite bounds are tested separately
+    unbounded: Vec<Arc<dyn Hittable>>,
+    bounds: Aabb,
+}
+
+impl Bvh {
+    pub fn new(objects: Vec<Arc<dyn Hittable>>) -> Self {
+        let mut unbounded = vec![];
+        let mut items = vec![];
+
+        for object in objects {
+            let bounds = object.bounding_box();
+
+            if bounds.is_empty() {
+                continue;
+            }
+
+            if bounds.is_finite() {
+                items.push(BuildItem {
+                    bounds,
+                    centroid: bounds.centroid(),
+                    object,
+                });
+            } else {
+                unbounded.push(object);
+            }
+        }
+
+        let mut nodes = Vec::with_capacity(2 * items.len());
+
+        if !items.is_empty() {
+            build(&mut nodes, &mut items, 0);
+        }
+
+        let mut bounds = nodes.first().map_or(Aabb::EMPTY, |node| node.bounds);
+
+        for object in &unbounded {
+            bounds = bounds.union(&object.bounding_box());
+        }
+
+        Self {
+            nodes,
+            objects: items.into_iter().map(|item| item.object).collect(),
+            unbounded,
+            bounds,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.objects.len() + self.unbounded.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<HittableList> for Bvh {
+    fn from(value: HittableList) -> Self {
+        Bvh::new(value.objects)
+    }
+}
+
+fn bounds_of(items: &[BuildItem]) -> (Aabb, Aabb) {
+    items
+        .iter()
+        .fold((Aabb::EMPTY, Aabb::EMPTY), |(b, c), item| {
+            (b.union(&item.bounds), c.grow(item.centroid))
+        })
+}
+
+fn push_leaf(nodes: &mut Vec<Node>, bounds: Aabb, start: usize, count: usize) {
+    nodes.push(Node {
+        bounds,
+        kind: NodeKind::Leaf { start, count },
+    });
+}
+
+// Returns (axis, split position, cost) of the cheapest binned split
+fn find_split(items: &[BuildItem], centroids: &Aabb) -> Option<(usize, f64, f64)> {
+    let mut best: Option<(usize, f64, f64)> = None;
+
+    for axis in 0..3 {
+        let low = centroids.min[axis];
+        let high = centroids.max[axis];
+
+        // All centroids coincide on this axis
+        if high <= low {
+            continue;
+        }
+
+        let scale = BINS as f64 / (high - low);
+        let mut bins = [Bin::default(); BINS];
+
+        for item in items {
+            let i = (((item.centroid[axis] - low) * scale) as usize).min(BINS - 1);
+            bins[i].count += 1;
+            bins[i].bounds = bins[i].bounds.union(&item.bounds);
+        }
+
+        // Sweep from the right to get the cost of each right partition
+        let mut right_area = [0.0; BINS - 1];
+        let mut right_count = [0; BINS - 1];
+        let mut acc = Bin::default();
+
+        for i in (1..BINS).rev() {
+            acc.count += bins[i].count;
+            acc.bounds = acc.bounds.union(&bins[i].bounds);
+            right_area[i - 1] = acc.bounds.surface_area();
+            right_count[i - 1] = acc.count;
+        }
+
+        let mut acc = Bin::default();
+
+        for i in 0..BINS - 1 {
+            acc.count += bins[i].count;
+            acc.bounds = acc.bounds.union(&bins[i].bounds);
+
+            let cost = acc.count as f64 * acc.bounds.surface_area()
+                + right_count[i] as f64 * right_area[i];
+
+            if best.is_none_or(|(_, _, best_cost)| cost < best_cost) {
+                let position = low + (i + 1) as f64 / scale;
+                best = Some((axis, position, cost));
+            }
+        }
+    }
+
+    best
+}
+
+fn build(nodes: &mut Vec<Node>, items: &mut [BuildItem], start: usize) {
+    let (bounds, centroids) = bounds_of(items);
+    let count = items.len();
+
+    if count <= 1 {
+        push_leaf(nodes, bounds, start, count);
+        return;
+    }
+
+    let split = find_split(items, &centroids);
+
+    let leaf_cost = INTERSECTION_COST * count as f64;
+
+    let mid = match split {
+        Some((axis, position, cost)) => {
+            let split_cost = TRAVERSAL_COST
+                + INTERSECTION_COST * cost / bounds.surface_area().max(f64::MIN_POSITIVE);
+
+            if count <= MAX_LEAF_SIZE && split_cost >= leaf_cost {
+                push_leaf(nodes, bounds, start, count);
+                return;
+            }
+
+            partition(items, |item| item.centroid[axis] < position)
+        }
+        None => {
+            // Every centroid is in the same place so SAH cannot help
+            if count <= MAX_LEAF_SIZE {
+                push_leaf(nodes, bounds, start, count);
+                return;
+            }
+
+            count / 2
+        }
+    };
+
+    // Binning can still produce an empty side with floating point error
+    let mid = if mid == 0 || mid == count {
+        count / 2
+    } else {
+        mid
+    };
+
+    let axis = split.map_or(centroids.longest_axis(), |(axis, _, _)| axis);
+
+    let index = nodes.len();
+    nodes.push(Node {
+        bounds,
+        kind: NodeKind::Interior { second: 0, axis },
+    });
+
+    let (left, right) = items.split_at_mut(mid);
+    build(nodes, left, start);
+
+    let second = nodes.len();
+    build(nodes, right, start + mid);
+
+    nodes[index].kind = NodeKind::Interior { second, axis };
+}
+
+fn partition<T>(items: &mut [T], predicate: impl Fn(&T) -> bool) -> usize {
+    let mut mid = 0;
+
+    for i in 0..items.len() {
+        if predicate(&items[i]) {
+            items.swap(i, mid);
+            mid += 1;
+        }
+    }
+
+    mid
+}
+
+impl Hittable for Bvh {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        let mut closest = max;
+        let mut out = None;
+
+        for object in &self.unbounded {
+            if let Some(hit) = object.hit(ray, min, closest) {
+                closest = hit.distance;
+                out = Some(hit);
+            }
+        }
+
+        if self.nodes.is_empty() {
+            return out;
+        }
+
+        let inv_direction = ray.direction.recip();
+        let negative = ray.direction.to_array().map(|x| x < 0.0);
+
+        let mut stack = Vec::with_capacity(64);
+        stack.push(0);
+
+        while let Some(index) = stack.pop() {
+            let node = &self.nodes[index];
+
+            if !node
+                .bounds
+                .hit_with_inverse(ray, inv_direction, min, closest)
+            {
+                continue;
+            }
+
+            match node.kind {
+                NodeKind::Leaf { start, count } => {
+                    for object in &self.objects[start..start + count] {
+                        if let Some(hit) = object.hit(ray, min, closest) {
+                            closest = hit.distance;
+                            out = Some(hit);
+                        }
+                    }
+                }
+                NodeKind::Interior { second, axis } => {
+                    let first = index + 1;
+
+                    // Visit the nearer child first so the far one can be culled
+                    let (near, far) = if negative[axis] {
+                        (second, first)
+                    } else {
+                        (first, second)
+                    };
+
+                    stack.push(far);
+                    stack.push(near);
+                }
+            }
+        }
+
+        out
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bounds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+    use crate::hittable::Sphere;
+    use crate::material::Lambertian;
+    use crate::vec::approx_eq;
+
+    fn spheres() -> Vec<Arc<dyn Hittable>> {
+        let material = Arc::new(Lambertian::new(dvec3(0.5, 0.5, 0.5)));
+        let mut out: Vec<Arc<dyn Hittable>> = vec![];
+
+        for x in -5..5 {
+            for z in -5..5 {
+                let position = dvec3(x as f64, 0.0, z as f64 + 0.1 * x as f64);
+                out.push(Arc::new(Sphere::new(position, 0.3, material.clone())));
+            }
+        }
+
+        out
+    }
+
+    #[test]
+    fn matches_linear_traversal() {
+        let list = HittableList::from(spheres());
+        let bvh = Bvh::new(spheres());
+
+        for i in 0..200 {
+            let t = i as f64 * 0.37;
+            let ray = Ray::new(
+                dvec3(t.sin() * 8.0, 3.0, t.cos() * 8.0),
+                dvec3(-t.sin() + 0.1 * t.cos(), -0.4, -t.cos()),
+            );
+
+            let expected = list.hit(&ray, 1e-9, f64::MAX).map(|hit| hit.distance);
+            let actual = bvh.hit(&ray, 1e-9, f64::MAX).map(|hit| hit.distance);
+
+            assert_eq!(expected, actual);
+        }
+    }
+
+    #[test]
+    fn bounds_cover_objects() {
+        let bvh = Bvh::new(spheres());
+        let bounds = bvh.bounding_box();
+
+        assert_eq!(bvh.len(), 100);
+        assert!(approx_eq(bounds.min, dvec3(-5.3, -0.3, -5.8)));
+        assert!(approx_eq(bounds.max, dvec3(4.3, 0.3, 4.7)));
+    }
+
+    #[test]
+    fn empty_bvh_misses() {
+        let bvh = Bvh::new(vec![]);
+        let ray = Ray::new(DVec3::ZERO, dvec3(1.0, 0.0, 0.0));
+
+        assert!(bvh.is_empty());
+        assert!(bvh.hit(&ray, 1e-9, f64::MAX).is_none());
+    }
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use glam::DVec2;
+
+use super::aabb::Aabb;
+use super::hit::Hit;
+use super::traits::Hittable;
+use crate::material::Material;
+use crate::random::random_exponential;
+use crate::ray::Ray;
+
+// Fog or smoke filling a closed boundary, rays scatter off the phase material
+// at random distances inside it
+pub struct ConstantMedium<H: Hittable> {
+    boundary: H,
+    density: f64,
+    phase: Arc<dyn Material>,
+}
+
+impl<H: Hittable> ConstantMedium<H> {
+    // Use Isotropic or HenyeyGreenstein for phase
+    pub fn new(boundary: H, density: f64, phase: Arc<dyn Material>) -> Self {
+        ConstantMedium {
+            boundary,
+            density,
+            phase,
+        }
+    }
+}
+
+impl<H: Hittable> Hittable for ConstantMedium<H> {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        // Boundary hits behind the ray are fine, it may start inside
+        let entry = self.boundary.hit(ray, f64::NEG_INFINITY, f64::INFINITY)?;
+        let exit = self
+            .boundary
+            .hit(ray, entry.distance + 1e-4, f64::INFINITY)?;
+
+        let start = entry.distance.max(min);
+        let end = exit.distance.min(max);
+
+        if start >= end {
+            return None;
+        }
+
+        let distance = start + random_exponential(self.density);
+
+        if distance >= end {
+            return None;
+        }
+
+        // Normal is meaningless inside a volume
+        Some(Hit::new(
+            ray.at(distance),
+            -ray.direction,
+            distance,
+            true,
+            DVec2::ZERO,
+            self.phase.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.boundary.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{dvec3, DVec3};
+
+    use super::*;
+    use crate::hittable::Sphere;
+    use crate::material::{Isotropic, Lambertian};
+
+    #[test]
+    fn transmittance_is_exponential() {
+        let boundary = Sphere::new(DVec3::ZERO, 1.0, Arc::new(Lambertian::new(DVec3::ONE)));
+        let medium = ConstantMedium::new(boundary, 0.5, Arc::new(Isotropic::new(DVec3::ONE)));
+
+        // Passes through 2 units of the medium
+        let ray = Ray::new(dvec3(0.0, 0.0, -5.0), DVec3::Z);
+        let n = 20_000;
+        let passed = (0..n)
+            .filter(|_| medium.hit(&ray, 1e-9, f64::MAX).is_none())
+            .count();
+
+        assert!((passed as f64 / n as f64 - (-1.0f64).exp()).abs() < 0.02);
+    }
+}
@@ -0,0 +1,230 @@
+use super::aabb::Aabb;
+use super::hit::Hit;
+use super::traits::Hittable;
+use crate::ray::Ray;
+
+// Intervals of a closed object from every crossing of its surface sorted by
+// distance, the line alternates between entering and leaving
+pub(crate) fn pair_crossings(crossings: Vec<Hit>) -> Vec<(Hit, Hit)> {
+    let mut crossings = crossings.into_iter();
+    let mut out = vec![];
+
+    while let (Some(entry), Some(exit)) = (crossings.next(), crossings.next()) {
+        out.push((entry, exit));
+    }
+
+    out
+}
+
+// Boolean combination of the spans inside a and b. Walks every boundary in
+// order and starts or ends a span whenever inside(in_a, in_b) changes
+fn combine(
+    a: Vec<(Hit, Hit)>,
+    b: Vec<(Hit, Hit)>,
+    inside: impl Fn(bool, bool) -> bool,
+) -> Vec<(Hit, Hit)> {
+    let mut events: Vec<(Hit, bool)> = a
+        .into_iter()
+        .flat_map(|(entry, exit)| [(entry, true), (exit, true)])
+        .chain(
+            b.into_iter()
+                .flat_map(|(entry, exit)| [(entry, false), (exit, false)]),
+        )
+        .collect();
+    events.sort_by(|x, y| x.0.distance.total_cmp(&y.0.distance));
+
+    let (mut in_a, mut in_b) = (false, false);
+    let mut entry = None;
+    let mut out = vec![];
+
+    for (mut hit, from_a) in events {
+        let was_inside = inside(in_a, in_b);
+
+        if from_a {
+            in_a = !in_a;
+        } else {
+            in_b = !in_b;
+        }
+
+        // Normals already face against the ray, only which side is the
+        // outside can change, e.g. where b's exit becomes a difference's entry
+        match (was_inside, inside(in_a, in_b)) {
+            (false, true) => {
+                hit.front_face = true;
+                entry = Some(hit);
+            }
+            (true, false) => {
+                hit.front_face = false;
+                out.extend(entry.take().map(|entry| (entry, hit)));
+            }
+            _ => {}
+        }
+    }
+
+    out
+}
+
+// First boundary of the combined spans between min and max
+fn first_hit(intervals: Vec<(Hit, Hit)>, min: f64, max: f64) -> Option<Hit> {
+    intervals
+        .into_iter()
+        .flat_map(|(entry, exit)| [entry, exit])
+        .find(|hit| (min..max).contains(&hit.distance))
+}
+
+// Constructive solid geometry, a and b must be closed objects that report
+// their intervals, like spheres or other CSG nodes
+pub struct Union<A: Hittable, B: Hittable> {
+    a: A,
+    b: B,
+}
+
+impl<A: Hittable, B: Hittable> Union<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Union { a, b }
+    }
+}
+
+impl<A: Hittable, B: Hittable> Hittable for Union<A, B> {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        first_hit(self.intervals(ray), min, max)
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<(Hit, Hit)> {
+        combine(self.a.intervals(ray), self.b.intervals(ray), |a, b| a || b)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.a.bounding_box().union(&self.b.bounding_box())
+    }
+}
+
+// Only where a and b overlap, e.g. a lens from two spheres
+pub struct Intersection<A: Hittable, B: Hittable> {
+    a: A,
+    b: B,
+}
+
+impl<A: Hittable, B: Hittable> Intersection<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Intersection { a, b }
+    }
+}
+
+impl<A: Hittable, B: Hittable> Hittable for Intersection<A, B> {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        first_hit(self.intervals(ray), min, max)
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<(Hit, Hit)> {
+        let a = self.a.intervals(ray);
+
+        // Nothing to intersect, skip b
+        if a.is_empty() {
+            return a;
+        }
+
+        combine(a, self.b.intervals(ray), |a, b| a && b)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.a.bounding_box().intersection(&self.b.bounding_box())
+    }
+}
+
+// a with b cut out of it, the cut surfaces use b's material
+pub struct Difference<A: Hittable, B: Hittable> {
+    a: A,
+    b: B,
+}
+
+impl<A: Hittable, B: Hittable> Difference<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Difference { a, b }
+    }
+}
+
+impl<A: Hittable, B: Hittable> Hittable for Difference<A, B> {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        first_hit(self.intervals(ray), min, max)
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<(Hit, Hit)> {
+        let a = self.a.intervals(ray);
+
+        if a.is_empty() {
+            return a;
+        }
+
+        combine(a, self.b.intervals(ray), |a, b| a && !b)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.a.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use glam::{dvec3, DVec3};
+
+    use super::*;
+    use crate::hittable::Sphere;
+    use crate::material::{Lambertian, Material};
+    use crate::vec::approx_eq;
+
+    fn sphere(x: f64) -> Sphere {
+        let material: Arc<dyn Material> = Arc::new(Lambertian::new(DVec3::ONE));
+        Sphere::new(dvec3(x, 0.0, 0.0), 1.0, material)
+    }
+
+    fn distances(intervals: Vec<(Hit, Hit)>) -> Vec<(f64, f64)> {
+        intervals
+            .into_iter()
+            .map(|(entry, exit)| {
+                assert!(entry.front_face && !exit.front_face);
+                (entry.distance, exit.distance)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn overlapping_spheres_along_x() {
+        // Spheres over [-1, 1] and [0, 2] on the x axis, ray starts at -5
+        let ray = Ray::new(dvec3(-5.0, 0.0, 0.0), DVec3::X);
+
+        let union = Union::new(sphere(0.0), sphere(1.0));
+        assert_eq!(distances(union.intervals(&ray)), vec![(4.0, 7.0)]);
+
+        let intersection = Intersection::new(sphere(0.0), sphere(1.0));
+        assert_eq!(distances(intersection.intervals(&ray)), vec![(5.0, 6.0)]);
+
+        let difference = Difference::new(sphere(0.0), sphere(1.0));
+        assert_eq!(distances(difference.intervals(&ray)), vec![(4.0, 5.0)]);
+
+        let apart = Intersection::new(sphere(0.0), sphere(3.0));
+        assert!(apart.hit(&ray, 1e-9, f64::MAX).is_none());
+    }
+
+    #[test]
+    fn subtracted_surface_faces_outwards() {
+        // Bite out of the +x side, a ray from the right enters through the
+        // inside of the smaller sphere
+        let bitten = Difference::new(sphere(0.0), sphere(1.0));
+        let ray = Ray::new(dvec3(5.0, 0.0, 0.0), -DVec3::X);
+
+        let hit = bitten.hit(&ray, 1e-9, f64::MAX).unwrap();
+        assert!((hit.distance - 5.0).abs() < 1e-9);
+        assert!(hit.front_face);
+        assert!(approx_eq(hit.normal, DVec3::X));
+
+        // Starting inside the solid part the exit is a back face
+        let ray = Ray::new(dvec3(-0.5, 0.0, 0.0), DVec3::X);
+        let hit = bitten.hit(&ray, 1e-9, f64::MAX).unwrap();
+        assert!((hit.distance - 0.5).abs() < 1e-9);
+        assert!(!hit.front_face);
+        assert!(approx_eq(hit.normal, -DVec3::X));
+    }
+}
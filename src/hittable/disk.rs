@@ -0,0 +1,158 @@
+use std::f64::consts::{PI, TAU};
+use std::sync::Arc;
+
+use glam::{dvec2, DVec3};
+
+use super::aabb::Aabb;
+use super::hit::Hit;
+use super::traits::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+
+// Flat circle, e.g. a round ceiling light
+pub struct Disk {
+    centre: DVec3,
+    normal: DVec3,
+    radius: f64,
+    material: Arc<dyn Material>,
+
+    // Directions along the disk, u = 0 points along tangent
+    tangent: DVec3,
+    bitangent: DVec3,
+}
+
+impl Disk {
+    pub fn new(centre: DVec3, normal: DVec3, radius: f64, material: Arc<dyn Material>) -> Self {
+        let normal = normal.normalize();
+        let (tangent, bitangent) = normal.any_orthonormal_pair();
+
+        Disk {
+            centre,
+            normal,
+            radius,
+            material,
+            tangent,
+            bitangent,
+        }
+    }
+}
+
+impl Hittable for Disk {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        let denominator = self.normal.dot(ray.direction);
+
+        // Parallel to the plane
+        if denominator.abs() < 1e-12 {
+            return None;
+        }
+
+        let distance = self.normal.dot(self.centre - ray.origin) / denominator;
+
+        if !(min..max).contains(&distance) {
+            return None;
+        }
+
+        let point = ray.at(distance);
+        let planar = point - self.centre;
+        let r = planar.length();
+
+        if r > self.radius {
+            return None;
+        }
+
+        let front_face = denominator < 0.0;
+
+        // Angle around the centre and distance from it
+        let phi = planar.dot(self.bitangent).atan2(planar.dot(self.tangent));
+        let uv = dvec2(phi.rem_euclid(TAU) / TAU, r / self.radius);
+
+        Some(Hit::new(
+            point,
+            if front_face {
+                self.normal
+            } else {
+                -self.normal
+            },
+            distance,
+            front_face,
+            uv,
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // How far the rim reaches along each axis
+        let extent = self.radius * (1.0 - self.normal * self.normal).max(DVec3::ZERO).powf(0.5);
+
+        // Padded as axis aligned disks have no thickness
+        Aabb::new(self.centre - extent, self.centre + extent).pad(1e-9)
+    }
+
+    fn pdf(&self, origin: DVec3, direction: DVec3, _time: f64) -> f64 {
+        let ray = Ray::new(origin, direction);
+
+        let Some(hit) = self.hit(&ray, 1e-9, f64::MAX) else {
+            return 0.0;
+        };
+
+        // Convert from area to solid angle measure
+        let distance_squared = hit.distance * hit.distance;
+        let cosine = ray.direction.dot(self.normal).abs();
+        let area = PI * self.radius * self.radius;
+
+        distance_squared / (cosine * area)
+    }
+
+    // Uniform over the area, sqrt stops points bunching in the middle
+    fn sample_direction(&self, origin: DVec3, _time: f64) -> Option<DVec3> {
+        let r = self.radius * fastrand::f64().sqrt();
+        let phi = TAU * fastrand::f64();
+
+        let point = self.centre + r * (phi.cos() * self.tangent + phi.sin() * self.bitangent);
+        Some(point - origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec::approx_eq_with_error;
+
+    fn disk() -> Disk {
+        Disk::new(
+            dvec3(0.0, 2.0, 0.0),
+            dvec3(0.0, -1.0, 0.0),
+            1.0,
+            Arc::new(Lambertian::new(DVec3::ONE)),
+        )
+    }
+
+    #[test]
+    fn hits_inside_radius() {
+        let up = dvec3(0.0, 1.0, 0.0);
+
+        let hit = disk().hit(&Ray::new(dvec3(0.6, 0.0, 0.6), up), 1e-9, f64::MAX);
+        assert!(hit.is_some_and(|hit| hit.front_face && (hit.uv.y - 0.72f64.sqrt()).abs() < 1e-9));
+
+        let hit = disk().hit(&Ray::new(dvec3(0.8, 0.0, 0.8), up), 1e-9, f64::MAX);
+        assert!(hit.is_none());
+    }
+
+    #[test]
+    fn bounds_and_pdf() {
+        let bounds = disk().bounding_box();
+        assert!(approx_eq_with_error(
+            bounds.min,
+            dvec3(-1.0, 2.0, -1.0),
+            1e-6
+        ));
+        assert!(approx_eq_with_error(bounds.max, dvec3(1.0, 2.0, 1.0), 1e-6));
+
+        // Area pi at distance 2 straight on
+        let pdf = disk().pdf(DVec3::ZERO, dvec3(0.0, 1.0, 0.0), 0.0);
+        assert!((pdf - 4.0 / PI).abs() < 1e-9);
+    }
+}
@@ -1,15 +1,21 @@
 use std::sync::Arc;
 
-use glam::DVec3;
+use glam::{DVec2, DVec3};
 
 use crate::material::Material;
+use crate::ray::Ray;
+use crate::sdf::OrbitTrap;
 
 pub struct Hit {
     pub point: DVec3,
     pub normal: DVec3,
     pub distance: f64,
     pub front_face: bool,
+    // Surface parameterisation, (0, 0) if the object has none
+    pub uv: DVec2,
     pub material: Arc<dyn Material>,
+    // Only set on fractal surfaces
+    pub trap: Option<OrbitTrap>,
 }
 
 impl Hit {
@@ -18,6 +24,7 @@ impl Hit {
         normal: DVec3,
         distance: f64,
         front_face: bool,
+        uv: DVec2,
         material: Arc<dyn Material>,
     ) -> Self {
         Hit {
@@ -25,7 +32,33 @@ impl Hit {
             normal,
             distance,
             front_face,
+            uv,
             material,
+            trap: None,
         }
     }
+
+    // Turns the normal to face against the ray and records which side it hit
+    pub fn from_outward_normal(
+        ray: &Ray,
+        distance: f64,
+        outward_normal: DVec3,
+        uv: DVec2,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        let front_face = outward_normal.dot(ray.direction) < 0.0;
+
+        Hit::new(
+            ray.at(distance),
+            if front_face {
+                outward_normal
+            } else {
+                -outward_normal
+            },
+            distance,
+            front_face,
+            uv,
+            material,
+        )
+    }
 }
@@ -1,5 +1,8 @@
 use std::sync::Arc;
 
+use glam::DVec3;
+
+use super::aabb::Aabb;
 use super::hit::Hit;
 use super::traits::Hittable;
 use crate::ray::Ray;
@@ -50,4 +53,33 @@ impl Hittable for HittableList {
 
         out
     }
+
+    fn bounding_box(&self) -> Aabb {
+        self.objects
+            .iter()
+            .fold(Aabb::EMPTY, |acc, object| acc.union(&object.bounding_box()))
+    }
+
+    // Each object is chosen with equal probability
+    fn pdf(&self, origin: DVec3, direction: DVec3, time: f64) -> f64 {
+        if self.objects.is_empty() {
+            return 0.0;
+        }
+
+        let sum: f64 = self
+            .objects
+            .iter()
+            .map(|object| object.pdf(origin, direction, time))
+            .sum();
+
+        sum / self.objects.len() as f64
+    }
+
+    fn sample_direction(&self, origin: DVec3, time: f64) -> Option<DVec3> {
+        if self.objects.is_empty() {
+            return None;
+        }
+
+        self.objects[fastrand::usize(..self.objects.len())].sample_direction(origin, time)
+    }
 }
@@ -0,0 +1,204 @@
+use glam::DVec3;
+
+use super::aabb::Aabb;
+use crate::sdf::Sdf;
+
+// Tom Lowe's Mandelbox, render with SdfHittable
+pub struct Mandelbox {
+    scale: f64,
+    fold_limit: f64,
+    min_radius: f64,
+    fixed_radius: f64,
+    iterations: usize,
+    bailout: f64,
+}
+
+impl Mandelbox {
+    pub fn new(scale: f64) -> Self {
+        Mandelbox {
+            scale,
+            fold_limit: 1.0,
+            min_radius: 0.5,
+            fixed_radius: 1.0,
+            iterations: 15,
+            bailout: 100.0,
+        }
+    }
+
+    // Points further than this from the origin on any axis are reflected back
+    pub fn with_fold_limit(mut self, fold_limit: f64) -> Self {
+        self.fold_limit = fold_limit;
+        self
+    }
+
+    // Spheres of inversion for the ball fold
+    pub fn with_radii(mut self, min_radius: f64, fixed_radius: f64) -> Self {
+        self.min_radius = min_radius;
+        self.fixed_radius = fixed_radius;
+        self
+    }
+
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn with_bailout(mut self, bailout: f64) -> Self {
+        self.bailout = bailout;
+        self
+    }
+}
+
+impl Mandelbox {
+    // Where the point ends up and the running derivative
+    fn orbit(&self, point: DVec3) -> (DVec3, f64) {
+        let min_r2 = self.min_radius * self.min_radius;
+        let fixed_r2 = self.fixed_radius * self.fixed_radius;
+
+        let mut z = point;
+        let mut dr = 1.0;
+
+        for _ in 0..self.iterations {
+            // Box fold
+            z = z.clamp(
+                DVec3::splat(-self.fold_limit),
+                DVec3::splat(self.fold_limit),
+            ) * 2.0
+                - z;
+
+            // Ball fold
+            let r2 = z.length_squared();
+            let factor = if r2 < min_r2 {
+                fixed_r2 / min_r2
+            } else if r2 < fixed_r2 {
+                fixed_r2 / r2
+            } else {
+                1.0
+            };
+            z *= factor;
+            dr *= factor;
+
+            z = z * self.scale + point;
+            dr = dr * self.scale.abs() + 1.0;
+
+            if z.length_squared() > self.bailout * self.bailout {
+                break;
+            }
+        }
+
+        (z, dr)
+    }
+}
+
+impl Sdf for Mandelbox {
+    fn distance(&self, point: DVec3) -> f64 {
+        let (z, dr) = self.orbit(point);
+        z.length() / dr.abs()
+    }
+
+    // Follows the largest coordinate m of a point outside the box. Past
+    // 2 fold_limit + the larger radius the ball fold leaves it alone and the
+    // box fold takes it to m - 2 fold_limit with its sign flipped, so after
+    // scaling and adding the point back it grows every iteration. Only
+    // bounded for |scale| > 1, e.g. [-7, 7] for scale 2
+    fn bounding_box(&self) -> Aabb {
+        let (s, fold) = (self.scale, self.fold_limit.abs());
+        let ball = self.min_radius.abs().max(self.fixed_radius.abs());
+
+        let half = if s < -1.0 {
+            // The point is added with the same sign as the folded coordinate
+            2.0 * fold + ball
+        } else if s > 1.0 {
+            // The first iteration flips the point's sign, so it has to be far
+            // enough out to land past 2 fold_limit + ball on the other side,
+            // and beyond the fixed point of m -> s (m - 2 fold_limit) + m0
+            let first = (2.0 * fold * (s + 1.0) + ball) / (s - 1.0);
+            let fixed = 2.0 * fold * s * s / (s * s - 2.0 * s + 2.0);
+            first.max(fixed).max(2.0 * fold + ball)
+        } else {
+            return Aabb::INFINITE;
+        };
+
+        Aabb::new(DVec3::splat(-half), DVec3::splat(half))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+
+    #[test]
+    fn origin_is_inside() {
+        // The origin is a fixed point of the folds so it never escapes
+        assert!(Mandelbox::new(2.0).distance(DVec3::ZERO) <= 0.0);
+    }
+
+    #[test]
+    fn bounds_contain_set() {
+        let mandelbox = Mandelbox::new(2.0);
+        let bounds = mandelbox.bounding_box();
+
+        // Everything outside the box escapes and is some way from the set
+        for i in 0..=32 {
+            for j in 0..=32 {
+                for k in 0..=32 {
+                    let point = dvec3(i as f64, j as f64, k as f64) * 0.5 - 8.0;
+
+                    if bounds.grow(point) != bounds {
+                        assert!(mandelbox.distance(point) > 0.1, "{point}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn points_just_outside_bounds_escape() {
+        let mandelboxes = [
+            Mandelbox::new(2.0),
+            // A min radius past the fixed one shrinks points, which reach
+            // further out than 2 (s + 1) / (s - 1) fold_limit
+            Mandelbox::new(2.0).with_radii(1.0, 0.3),
+            Mandelbox::new(2.0).with_radii(4.0, 0.1),
+            Mandelbox::new(1.5)
+                .with_fold_limit(0.8)
+                .with_radii(1.5, 0.5),
+            Mandelbox::new(-1.5),
+            Mandelbox::new(-2.0).with_radii(4.0, 1.0),
+        ];
+
+        for mandelbox in mandelboxes {
+            let mandelbox = mandelbox.with_iterations(500);
+            let half = mandelbox.bounding_box().max.x + 1e-3;
+
+            for _ in 0..1000 {
+                let mut point = (crate::random::random_cube() * 2.0 - 1.0) * half;
+                point[fastrand::usize(0..3)] = if fastrand::bool() { half } else { -half };
+
+                let (z, _) = mandelbox.orbit(point);
+                assert!(
+                    z.length() > mandelbox.bailout,
+                    "{} {point}",
+                    mandelbox.scale
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn iterations_and_bailout_matter() {
+        let point = dvec3(7.0, 0.0, 0.0);
+        let distance = Mandelbox::new(2.0).distance(point);
+
+        assert_ne!(
+            Mandelbox::new(2.0).with_iterations(1).distance(point),
+            distance
+        );
+        assert_ne!(
+            Mandelbox::new(2.0).with_bailout(8.0).distance(point),
+            distance
+        );
+    }
+}
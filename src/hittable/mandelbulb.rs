@@ -1,40 +1,75 @@
-use std::sync::Arc;
-
 use glam::{dvec3, DVec3};
 
-use super::hit::Hit;
-use super::traits::Hittable;
-use crate::material::{Material, Rainbow};
-use crate::ray::Ray;
+use super::aabb::Aabb;
+use crate::sdf::{OrbitTrap, Sdf, Traps};
 
+// Render with SdfHittable, position it with the SdfExt transforms
 pub struct Mandelbulb {
-    material: Arc<dyn Material>,
     power: f64,
+    iterations: usize,
+    bailout: f64,
+    // Added every iteration instead of the starting point if set
+    julia: Option<DVec3>,
+    traps: Traps,
 }
 
 impl Mandelbulb {
     pub fn new(power: f64) -> Self {
         Mandelbulb {
-            material: Arc::new(Rainbow::new()),
             power,
+            iterations: 32,
+            bailout: 10.0,
+            julia: None,
+            traps: Traps::default(),
         }
     }
 
-    fn distance_equation(&self, c: DVec3) -> f64 {
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn with_bailout(mut self, bailout: f64) -> Self {
+        self.bailout = bailout;
+        self
+    }
+
+    pub fn with_julia(mut self, julia: DVec3) -> Self {
+        self.julia = Some(julia);
+        self
+    }
+
+    // Where orbit trap distances are measured from
+    pub fn with_traps(mut self, traps: Traps) -> Self {
+        self.traps = traps;
+        self
+    }
+
+    // Fills in trap as it goes if given one
+    fn distance_equation(&self, c: DVec3, mut trap: Option<&mut OrbitTrap>) -> f64 {
         let mut z = c;
         let mut dr = 1.0;
         let mut r = 0.0;
+        let mut iterations = self.iterations;
+        let offset = self.julia.unwrap_or(c);
+        // c changes with the point in the Mandelbulb but is fixed for Julia sets
+        let dc = if self.julia.is_some() { 0.0 } else { 1.0 };
 
-        for _ in 0..32 {
+        for i in 0..self.iterations {
             r = z.length();
 
-            if r > 10.0 {
+            if r > self.bailout {
+                iterations = i;
                 break;
             }
 
+            if let Some(trap) = trap.as_deref_mut() {
+                trap.update(z, &self.traps);
+            }
+
             let mut theta = (z.z / r).acos();
             let mut phi = (z.y / z.x).atan();
-            dr = r.powf(self.power - 1.0) * self.power * dr + 1.0;
+            dr = r.powf(self.power - 1.0) * self.power * dr + dc;
 
             let zr = r.powf(self.power);
             theta *= self.power;
@@ -46,39 +81,81 @@ impl Mandelbulb {
                     theta.sin() * phi.sin(),
                     theta.cos(),
                 );
-            z += c;
+            z += offset;
+        }
+
+        if let Some(trap) = trap {
+            trap.iterations = iterations;
+            trap.smooth = if iterations < self.iterations {
+                // Fraction of the last iteration from how far past the bailout it went
+                let overshoot = (r.ln() / self.bailout.ln()).ln() / self.power.ln();
+                (iterations as f64 + 1.0 - overshoot).max(0.0)
+            } else {
+                iterations as f64
+            };
         }
 
         0.5 * r.ln() * r / dr
     }
 }
 
-impl Hittable for Mandelbulb {
-    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
-        let mut distance = min;
-        let max_iters = 128;
+impl Sdf for Mandelbulb {
+    fn distance(&self, point: DVec3) -> f64 {
+        self.distance_equation(point, None)
+    }
 
-        for n in 0..max_iters {
-            let delta = self.distance_equation(ray.at(distance));
-            distance += delta;
+    fn bounding_box(&self) -> Aabb {
+        if self.power <= 1.0 {
+            return Aabb::INFINITE;
+        }
 
-            if delta > 0.0002 {
-                continue;
-            }
+        // Points with |z| > r^(1 / (power - 1)) always escape, where r is 2 for
+        // the Mandelbulb and 1 + |julia| for Julia sets
+        let base = self.julia.map_or(2.0, |julia| 1.0 + julia.length());
+        let radius = base.powf(1.0 / (self.power - 1.0));
+        Aabb::new(DVec3::splat(-radius), DVec3::splat(radius))
+    }
 
-            if !(min..max).contains(&distance) {
-                continue;
-            }
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        let mut trap = OrbitTrap::new();
+        self.distance_equation(point, Some(&mut trap));
+        Some(trap)
+    }
+}
 
-            return Some(Hit::new(
-                ray.at(distance),
-                ray.direction,
-                n as f64 / max_iters as f64,
-                false,
-                self.material.clone(),
-            ));
-        }
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+
+    #[test]
+    fn small_julia_is_nearly_unit_ball() {
+        let ball = Mandelbulb::new(8.0).with_julia(DVec3::splat(0.1));
+
+        assert!(ball.distance(dvec3(0.5, 0.1, 0.2)) < 0.0);
+        assert!(ball.distance(dvec3(0.1, 1.5, 0.2)) > 0.0);
+        assert!(ball.bounding_box().max.x < 1.1);
+    }
+
+    #[test]
+    fn julia_derivative_has_no_offset() {
+        // With c = 0 the radius squares each step, 2 4 16 256, and the
+        // derivative is 2 r dr with nothing added, 1 4 32 1024
+        let julia = Mandelbulb::new(2.0)
+            .with_julia(DVec3::ZERO)
+            .with_bailout(100.0);
+        let expected = 0.5 * 256.0f64.ln() * 256.0 / 1024.0;
+
+        assert!((julia.distance(dvec3(2.0, 0.0, 0.0)) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn iterations_limit_trap() {
+        let bulb = Mandelbulb::new(8.0).with_iterations(5);
+        let trap = bulb.orbit_trap(DVec3::ZERO).unwrap();
 
-        None
+        assert_eq!(trap.iterations, 5);
+        assert_eq!(trap.point, 0.0);
     }
 }
@@ -0,0 +1,89 @@
+use glam::DVec3;
+
+use super::aabb::Aabb;
+use crate::sdf::Sdf;
+
+// Inigo Quilez's exact distance to the sponge, filling [-1, 1]^3
+// Render with SdfHittable
+pub struct MengerSponge {
+    iterations: usize,
+    bailout: f64,
+}
+
+impl MengerSponge {
+    pub fn new() -> Self {
+        MengerSponge {
+            iterations: 5,
+            bailout: 1.0,
+        }
+    }
+
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    // Points this far from the sponge skip the remaining holes, the result
+    // is still a lower bound so marching stays safe
+    pub fn with_bailout(mut self, bailout: f64) -> Self {
+        self.bailout = bailout;
+        self
+    }
+}
+
+impl Default for MengerSponge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sdf for MengerSponge {
+    fn distance(&self, point: DVec3) -> f64 {
+        let q = point.abs() - DVec3::ONE;
+        let mut distance = q.max(DVec3::ZERO).length() + q.max_element().min(0.0);
+        let mut scale = 1.0;
+
+        for _ in 0..self.iterations {
+            if distance > self.bailout {
+                break;
+            }
+
+            // Distance to the cross shaped hole through the current cell
+            let a = (point * scale).rem_euclid(DVec3::splat(2.0)) - 1.0;
+            scale *= 3.0;
+            let r = (1.0 - 3.0 * a.abs()).abs();
+
+            let da = r.x.max(r.y);
+            let db = r.y.max(r.z);
+            let dc = r.z.max(r.x);
+            let hole = (da.min(db).min(dc) - 1.0) / scale;
+
+            distance = distance.max(hole);
+        }
+
+        distance
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(-DVec3::ONE, DVec3::ONE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+
+    #[test]
+    fn centre_holes_are_empty() {
+        let sponge = MengerSponge::new();
+
+        // Middle of the cube and of each face are removed at the first level
+        assert!((sponge.distance(DVec3::ZERO) - 1.0 / 3.0).abs() < 1e-9);
+        assert!(sponge.distance(dvec3(0.0, 0.0, 0.9)) > 0.0);
+        // Corners stay solid
+        assert!(sponge.distance(dvec3(0.95, 0.95, 0.95)) < 0.0);
+        assert!((sponge.distance(dvec3(3.0, 0.0, 0.0)) - 2.0).abs() < 1e-9);
+    }
+}
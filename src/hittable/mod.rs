@@ -1,11 +1,48 @@
+pub mod aabb;
+pub mod boxshape;
+pub mod bvh;
+pub mod constantmedium;
+pub mod csg;
+pub mod disk;
 pub mod hit;
 pub mod hittablelist;
+pub mod mandelbox;
 pub mod mandelbulb;
+pub mod mengersponge;
+pub mod plane;
+mod polynomial;
+pub mod quad;
+pub mod quadric;
+pub mod quaternionjulia;
+pub mod sdfhittable;
+pub mod sierpinski;
 pub mod sphere;
+pub mod torus;
 pub mod traits;
+pub mod transformed;
+pub mod triangle;
+pub mod trianglemesh;
 
+pub use aabb::Aabb;
+pub use boxshape::BoxShape;
+pub use bvh::Bvh;
+pub use constantmedium::ConstantMedium;
+pub use csg::{Difference, Intersection, Union};
+pub use disk::Disk;
 pub use hit::Hit;
 pub use hittablelist::HittableList;
+pub use mandelbox::Mandelbox;
 pub use mandelbulb::Mandelbulb;
+pub use mengersponge::MengerSponge;
+pub use plane::Plane;
+pub use quad::Quad;
+pub use quadric::Quadric;
+pub use quaternionjulia::QuaternionJulia;
+pub use sdfhittable::SdfHittable;
+pub use sierpinski::Sierpinski;
 pub use sphere::Sphere;
+pub use torus::Torus;
 pub use traits::Hittable;
+pub use transformed::Transformed;
+pub use triangle::Triangle;
+pub use trianglemesh::{Face, TriangleMesh};
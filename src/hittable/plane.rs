@@ -0,0 +1,103 @@
+use std::sync::Arc;
+
+use glam::{dvec2, DVec3};
+
+use super::aabb::Aabb;
+use super::hit::Hit;
+use super::traits::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+
+// Infinite plane through point, for floors and walls without the precision
+// problems of a huge sphere
+pub struct Plane {
+    point: DVec3,
+    normal: DVec3,
+    material: Arc<dyn Material>,
+
+    // Directions along the plane the uvs follow
+    tangent: DVec3,
+    bitangent: DVec3,
+}
+
+impl Plane {
+    pub fn new(point: DVec3, normal: DVec3, material: Arc<dyn Material>) -> Self {
+        let normal = normal.normalize();
+        let (tangent, bitangent) = normal.any_orthonormal_pair();
+
+        Plane {
+            point,
+            normal,
+            material,
+            tangent,
+            bitangent,
+        }
+    }
+}
+
+impl Hittable for Plane {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        let denominator = self.normal.dot(ray.direction);
+
+        // Parallel to the plane
+        if denominator.abs() < 1e-12 {
+            return None;
+        }
+
+        let distance = self.normal.dot(self.point - ray.origin) / denominator;
+
+        if !(min..max).contains(&distance) {
+            return None;
+        }
+
+        let point = ray.at(distance);
+        let front_face = denominator < 0.0;
+
+        // Distances along the plane so textures tile once per unit
+        let planar = point - self.point;
+
+        Some(Hit::new(
+            point,
+            if front_face {
+                self.normal
+            } else {
+                -self.normal
+            },
+            distance,
+            front_face,
+            dvec2(planar.dot(self.tangent), planar.dot(self.bitangent)),
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::INFINITE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec::approx_eq;
+
+    #[test]
+    fn grazing_hit_stays_on_plane() {
+        let plane = Plane::new(DVec3::ZERO, DVec3::Y, Arc::new(Lambertian::new(DVec3::ONE)));
+
+        let ray = Ray::new(dvec3(0.0, 1.0, 0.0), dvec3(1e4, -1.0, 0.0));
+        let hit = plane.hit(&ray, 1e-9, f64::MAX).unwrap();
+
+        assert!(hit.point.y.abs() < 1e-9);
+        assert!((hit.point.x - 1e4).abs() < 1e-6);
+        assert!(hit.front_face);
+        assert!(approx_eq(hit.normal, DVec3::Y));
+
+        let ray = Ray::new(dvec3(0.0, -1.0, 0.0), dvec3(0.0, 1.0, 0.0));
+        let hit = plane.hit(&ray, 1e-9, f64::MAX).unwrap();
+        assert!(!hit.front_face);
+        assert!(approx_eq(hit.normal, -DVec3::Y));
+    }
+}
@@ -0,0 +1,124 @@
+// Real roots of polynomials for intersecting curved surfaces
+
+// Roots of a x^2 + b x + c in increasing order, including the linear case
+pub fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < 1e-12 {
+        if b.abs() < 1e-12 {
+            return vec![];
+        }
+
+        return vec![-c / b];
+    }
+
+    let discriminant = b * b - 4.0 * a * c;
+
+    if discriminant < 0.0 {
+        return vec![];
+    }
+
+    // Avoids cancellation when b is much larger than a c
+    let q = -0.5 * (b + b.signum() * discriminant.sqrt());
+
+    if q == 0.0 {
+        return vec![0.0, 0.0];
+    }
+
+    let (x0, x1) = (q / a, c / q);
+    vec![x0.min(x1), x0.max(x1)]
+}
+
+fn evaluate(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, &c| acc * x + c)
+}
+
+// Roots between low and high in increasing order, coefficients go from the
+// constant term upwards. Between neighbouring roots of the derivative the
+// polynomial is monotonic, so each root can be found by bisection. Touching
+// roots that don't cross zero can be missed
+pub fn roots_between(coefficients: &[f64], low: f64, high: f64) -> Vec<f64> {
+    match coefficients.len() {
+        0 | 1 => return vec![],
+        2 => {
+            let root = -coefficients[0] / coefficients[1];
+            return if (low..=high).contains(&root) {
+                vec![root]
+            } else {
+                vec![]
+            };
+        }
+        _ => {}
+    }
+
+    let derivative: Vec<f64> = coefficients
+        .iter()
+        .enumerate()
+        .skip(1)
+        .map(|(i, c)| c * i as f64)
+        .collect();
+
+    let mut bounds = vec![low];
+    bounds.extend(roots_between(&derivative, low, high));
+    bounds.push(high);
+
+    bounds
+        .windows(2)
+        .filter_map(|window| bisect(coefficients, window[0], window[1]))
+        .collect()
+}
+
+fn bisect(coefficients: &[f64], mut low: f64, mut high: f64) -> Option<f64> {
+    let mut f_low = evaluate(coefficients, low);
+    let f_high = evaluate(coefficients, high);
+
+    if f_low == 0.0 {
+        return Some(low);
+    }
+
+    if f_low.signum() == f_high.signum() {
+        return None;
+    }
+
+    // Enough halvings to reach double precision on any sensible interval
+    for _ in 0..64 {
+        let middle = 0.5 * (low + high);
+        let f_middle = evaluate(coefficients, middle);
+
+        if f_middle.signum() == f_low.signum() {
+            low = middle;
+            f_low = f_middle;
+        } else {
+            high = middle;
+        }
+    }
+
+    Some(0.5 * (low + high))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quartic_roots() {
+        // (x + 2)(x + 0.5)(x - 1)(x - 3)
+        let roots = roots_between(&[3.0, 3.5, -6.0, -1.5, 1.0], -10.0, 10.0);
+        let expected = [-2.0, -0.5, 1.0, 3.0];
+
+        assert_eq!(roots.len(), 4);
+        for (root, expected) in roots.iter().zip(expected) {
+            assert!((root - expected).abs() < 1e-9);
+        }
+
+        assert_eq!(
+            roots_between(&[3.0, 3.5, -6.0, -1.5, 1.0], 0.0, 2.0).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn quadratic_roots() {
+        assert_eq!(solve_quadratic(1.0, -3.0, 2.0), vec![1.0, 2.0]);
+        assert_eq!(solve_quadratic(0.0, 2.0, -1.0), vec![0.5]);
+        assert!(solve_quadratic(1.0, 0.0, 1.0).is_empty());
+    }
+}
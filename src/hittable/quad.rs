@@ -0,0 +1,155 @@
+use std::sync::Arc;
+
+use glam::{dvec2, DVec3};
+
+use super::aabb::Aabb;
+use super::hit::Hit;
+use super::traits::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+
+// Parallelogram with corners origin, origin + u, origin + v and origin + u + v
+pub struct Quad {
+    origin: DVec3,
+    u: DVec3,
+    v: DVec3,
+    material: Arc<dyn Material>,
+
+    // Cached plane values
+    normal: DVec3,
+    d: f64,
+    w: DVec3,
+    area: f64,
+}
+
+impl Quad {
+    pub fn new(origin: DVec3, u: DVec3, v: DVec3, material: Arc<dyn Material>) -> Self {
+        let n = u.cross(v);
+        let normal = n.normalize();
+
+        Quad {
+            origin,
+            u,
+            v,
+            material,
+            normal,
+            d: normal.dot(origin),
+            w: n / n.length_squared(),
+            area: n.length(),
+        }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        let denominator = self.normal.dot(ray.direction);
+
+        // Parallel to the plane
+        if denominator.abs() < 1e-12 {
+            return None;
+        }
+
+        let distance = (self.d - self.normal.dot(ray.origin)) / denominator;
+
+        if !(min..max).contains(&distance) {
+            return None;
+        }
+
+        // Coordinates of the hit in terms of u and v
+        let point = ray.at(distance);
+        let planar = point - self.origin;
+        let alpha = self.w.dot(planar.cross(self.v));
+        let beta = self.w.dot(self.u.cross(planar));
+
+        if !(0.0..=1.0).contains(&alpha) || !(0.0..=1.0).contains(&beta) {
+            return None;
+        }
+
+        let front_face = denominator < 0.0;
+
+        Some(Hit::new(
+            point,
+            if front_face {
+                self.normal
+            } else {
+                -self.normal
+            },
+            distance,
+            front_face,
+            dvec2(alpha, beta),
+            self.material.clone(),
+        ))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // Padded as axis aligned quads have no thickness
+        Aabb::from_points([
+            self.origin,
+            self.origin + self.u,
+            self.origin + self.v,
+            self.origin + self.u + self.v,
+        ])
+        .pad(1e-9)
+    }
+
+    fn pdf(&self, origin: DVec3, direction: DVec3, _time: f64) -> f64 {
+        let ray = Ray::new(origin, direction);
+
+        let Some(hit) = self.hit(&ray, 1e-9, f64::MAX) else {
+            return 0.0;
+        };
+
+        // Convert from area to solid angle measure
+        let distance_squared = hit.distance * hit.distance;
+        let cosine = ray.direction.dot(self.normal).abs();
+
+        distance_squared / (cosine * self.area)
+    }
+
+    fn sample_direction(&self, origin: DVec3, _time: f64) -> Option<DVec3> {
+        let point = self.origin + fastrand::f64() * self.u + fastrand::f64() * self.v;
+        Some(point - origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec::approx_eq;
+
+    fn quad() -> Quad {
+        Quad::new(
+            dvec3(-1.0, 2.0, -1.0),
+            dvec3(2.0, 0.0, 0.0),
+            dvec3(0.0, 0.0, 2.0),
+            Arc::new(Lambertian::new(DVec3::ONE)),
+        )
+    }
+
+    #[test]
+    fn hit_reports_uv() {
+        let ray = Ray::new(dvec3(0.5, 0.0, -0.5), dvec3(0.0, 1.0, 0.0));
+        let hit = quad().hit(&ray, 1e-9, f64::MAX).unwrap();
+
+        assert!(approx_eq(hit.point, dvec3(0.5, 2.0, -0.5)));
+        assert!((hit.uv - dvec2(0.75, 0.25)).length() < 1e-9);
+    }
+
+    #[test]
+    fn miss_outside() {
+        let ray = Ray::new(dvec3(1.5, 0.0, 0.0), dvec3(0.0, 1.0, 0.0));
+
+        assert!(quad().hit(&ray, 1e-9, f64::MAX).is_none());
+    }
+
+    #[test]
+    fn pdf_directly_below() {
+        // Area 4 at distance 2 straight on
+        let pdf = quad().pdf(DVec3::ZERO, dvec3(0.0, 1.0, 0.0), 0.0);
+
+        assert!((pdf - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,252 @@
+use std::f64::consts::TAU;
+use std::sync::Arc;
+
+use glam::{dvec2, dvec3, DVec2, DVec3, Vec3Swizzles};
+
+use super::aabb::Aabb;
+use super::csg::pair_crossings;
+use super::hit::Hit;
+use super::polynomial::solve_quadratic;
+use super::traits::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+
+// Surface of revolution around the y axis where x^2 + z^2 = c0 + c1 y + c2 y^2
+// between -half_height and half_height. Covers cylinders, cones, paraboloids
+// and hyperboloids, place them with Transformed
+pub struct Quadric {
+    coefficients: [f64; 3],
+    half_height: f64,
+    // Disks closing off the ends, only drawn where the radius isn't zero
+    caps: bool,
+    material: Arc<dyn Material>,
+}
+
+impl Quadric {
+    pub fn cylinder(radius: f64, half_height: f64, material: Arc<dyn Material>) -> Self {
+        Quadric::new([radius * radius, 0.0, 0.0], half_height, material)
+    }
+
+    // Base of the given radius at the bottom, apex at the top
+    pub fn cone(radius: f64, half_height: f64, material: Arc<dyn Material>) -> Self {
+        // radius * (h - y) / 2h squared
+        let k = radius / (2.0 * half_height);
+        let h = half_height;
+        Quadric::new(
+            [k * k * h * h, -2.0 * k * k * h, k * k],
+            half_height,
+            material,
+        )
+    }
+
+    // Vertex at the bottom, opening to the given radius at the top
+    pub fn paraboloid(radius: f64, half_height: f64, material: Arc<dyn Material>) -> Self {
+        // radius^2 (y + h) / 2h
+        let k = radius * radius / (2.0 * half_height);
+        Quadric::new([k * half_height, k, 0.0], half_height, material)
+    }
+
+    // One sheet, narrowest in the middle and widening to end_radius at the
+    // top and bottom. A smaller end_radius bulges outwards instead
+    pub fn hyperboloid(
+        waist_radius: f64,
+        end_radius: f64,
+        half_height: f64,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        let curvature =
+            (end_radius * end_radius - waist_radius * waist_radius) / (half_height * half_height);
+        Quadric::new(
+            [waist_radius * waist_radius, 0.0, curvature],
+            half_height,
+            material,
+        )
+    }
+
+    fn new(coefficients: [f64; 3], half_height: f64, material: Arc<dyn Material>) -> Self {
+        Quadric {
+            coefficients,
+            half_height,
+            caps: true,
+            material,
+        }
+    }
+
+    // Uncapped shapes are open tubes and don't work in CSG
+    pub fn with_caps(mut self, caps: bool) -> Self {
+        self.caps = caps;
+        self
+    }
+
+    // Squared radius at height y
+    fn radius_squared(&self, y: f64) -> f64 {
+        let [c0, c1, c2] = self.coefficients;
+        c0 + c1 * y + c2 * y * y
+    }
+
+    // Every crossing of the surface along the ray's line, nearest first
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        let [c0, c1, c2] = self.coefficients;
+        let (o, d) = (ray.origin, ray.direction);
+        let h = self.half_height;
+
+        let a = d.xz().length_squared() - c2 * d.y * d.y;
+        let b = 2.0 * o.xz().dot(d.xz()) - c1 * d.y - 2.0 * c2 * o.y * d.y;
+        let c = o.xz().length_squared() - c0 - c1 * o.y - c2 * o.y * o.y;
+
+        let mut out: Vec<Hit> = solve_quadratic(a, b, c)
+            .into_iter()
+            .filter(|&t| (-h..=h).contains(&ray.at(t).y))
+            .map(|t| {
+                let point = ray.at(t);
+
+                // Gradient of x^2 + z^2 - r^2(y), falls back at a cone's apex
+                let slope = c1 + 2.0 * c2 * point.y;
+                let normal = dvec3(point.x, -0.5 * slope, point.z).normalize_or(DVec3::Y);
+
+                let u = (-point.z).atan2(point.x).rem_euclid(TAU) / TAU;
+                let v = (point.y + h) / (2.0 * h);
+
+                Hit::from_outward_normal(ray, t, normal, dvec2(u, v), self.material.clone())
+            })
+            .collect();
+
+        if self.caps && d.y != 0.0 {
+            for (y, normal) in [(-h, -DVec3::Y), (h, DVec3::Y)] {
+                let radius_squared = self.radius_squared(y);
+                let t = (y - o.y) / d.y;
+                let point = ray.at(t);
+
+                if radius_squared > 0.0 && point.xz().length_squared() <= radius_squared {
+                    let uv = cap_uv(point, radius_squared.sqrt());
+                    out.push(Hit::from_outward_normal(
+                        ray,
+                        t,
+                        normal,
+                        uv,
+                        self.material.clone(),
+                    ));
+                }
+            }
+        }
+
+        out.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        out
+    }
+}
+
+// Angle around the cap and distance from its centre like Disk
+fn cap_uv(point: DVec3, radius: f64) -> DVec2 {
+    let u = (-point.z).atan2(point.x).rem_euclid(TAU) / TAU;
+    dvec2(u, point.xz().length() / radius)
+}
+
+impl Hittable for Quadric {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        self.crossings(ray)
+            .into_iter()
+            .find(|hit| (min..max).contains(&hit.distance))
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<(Hit, Hit)> {
+        if !self.caps {
+            return vec![];
+        }
+
+        pair_crossings(self.crossings(ray))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let [_, c1, c2] = self.coefficients;
+        let h = self.half_height;
+
+        // Widest at an end or where the radius turns around
+        let mut heights = vec![-h, h];
+        if c2 < 0.0 {
+            heights.push((-c1 / (2.0 * c2)).clamp(-h, h));
+        }
+
+        let radius = heights
+            .into_iter()
+            .map(|y| self.radius_squared(y).max(0.0).sqrt())
+            .fold(0.0, f64::max);
+
+        Aabb::new(dvec3(-radius, -h, -radius), dvec3(radius, h, radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec::approx_eq;
+
+    fn material() -> Arc<dyn Material> {
+        Arc::new(Lambertian::new(DVec3::ONE))
+    }
+
+    #[test]
+    fn cylinder_side_and_caps() {
+        let cylinder = Quadric::cylinder(1.0, 2.0, material());
+
+        let ray = Ray::new(dvec3(5.0, 0.5, 0.0), -DVec3::X);
+        let hit = cylinder.hit(&ray, 1e-9, f64::MAX).unwrap();
+        assert!((hit.distance - 4.0).abs() < 1e-9);
+        assert!(hit.front_face);
+        assert!(approx_eq(hit.normal, DVec3::X));
+        assert!((hit.uv.y - 0.625).abs() < 1e-9);
+
+        // Down the axis only the caps are in the way
+        let ray = Ray::new(dvec3(0.0, 5.0, 0.0), -DVec3::Y);
+        let intervals = cylinder.intervals(&ray);
+        assert_eq!(intervals.len(), 1);
+        assert!((intervals[0].0.distance - 3.0).abs() < 1e-9);
+        assert!((intervals[0].1.distance - 7.0).abs() < 1e-9);
+
+        // From inside an uncapped tube the wall is a back face
+        let tube = Quadric::cylinder(1.0, 2.0, material()).with_caps(false);
+        assert!(tube.hit(&ray, 1e-9, f64::MAX).is_none());
+
+        let hit = tube
+            .hit(&Ray::new(DVec3::ZERO, DVec3::Z), 1e-9, f64::MAX)
+            .unwrap();
+        assert!(!hit.front_face);
+        assert!(approx_eq(hit.normal, -DVec3::Z));
+        assert!(tube.intervals(&ray).is_empty());
+    }
+
+    #[test]
+    fn cone_slope() {
+        // Radius 1 at the bottom, 0.5 half way up, 0 at the apex
+        let cone = Quadric::cone(1.0, 1.0, material());
+
+        let ray = Ray::new(dvec3(5.0, 0.0, 0.0), -DVec3::X);
+        let hit = cone.hit(&ray, 1e-9, f64::MAX).unwrap();
+        assert!((hit.distance - 4.5).abs() < 1e-9);
+        assert!(approx_eq(hit.normal, dvec3(2.0, 1.0, 0.0).normalize()));
+
+        let bounds = cone.bounding_box();
+        assert!(approx_eq(bounds.max, dvec3(1.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn paraboloid_and_hyperboloid() {
+        // x^2 + z^2 = y + 1, radius sqrt(2) at the top
+        let bowl = Quadric::paraboloid(2.0f64.sqrt(), 1.0, material());
+        let ray = Ray::new(dvec3(0.0, 5.0, 0.0), -DVec3::Y);
+        let intervals = bowl.intervals(&ray);
+        assert!((intervals[0].0.distance - 4.0).abs() < 1e-9);
+        assert!((intervals[0].1.distance - 6.0).abs() < 1e-9);
+        assert!(approx_eq(intervals[0].1.normal, DVec3::Y));
+
+        // Waist 1, radius 2 at the ends
+        let hyperboloid = Quadric::hyperboloid(1.0, 2.0, 1.0, material());
+        let ray = Ray::new(dvec3(5.0, 0.0, 0.0), -DVec3::X);
+        let hit = hyperboloid.hit(&ray, 1e-9, f64::MAX).unwrap();
+        assert!((hit.distance - 4.0).abs() < 1e-9);
+        assert!(approx_eq(
+            hyperboloid.bounding_box().max,
+            dvec3(2.0, 1.0, 2.0)
+        ));
+    }
+}
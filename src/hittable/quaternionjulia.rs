@@ -0,0 +1,126 @@
+use glam::{DVec3, DVec4};
+
+use super::aabb::Aabb;
+use crate::sdf::Sdf;
+
+// z -> z^2 + c over quaternions stored as (real, i, j, k)
+fn square(q: DVec4) -> DVec4 {
+    DVec4::new(
+        q.x * q.x - q.y * q.y - q.z * q.z - q.w * q.w,
+        2.0 * q.x * q.y,
+        2.0 * q.x * q.z,
+        2.0 * q.x * q.w,
+    )
+}
+
+// 3D slice through a 4D quaternion Julia set, render with SdfHittable
+pub struct QuaternionJulia {
+    c: DVec4,
+    // Fixed k component of every point in the slice
+    slice: f64,
+    iterations: usize,
+    bailout: f64,
+}
+
+impl QuaternionJulia {
+    pub fn new(c: DVec4) -> Self {
+        QuaternionJulia {
+            c,
+            slice: 0.0,
+            iterations: 12,
+            bailout: 10.0,
+        }
+    }
+
+    pub fn with_slice(mut self, slice: f64) -> Self {
+        self.slice = slice;
+        self
+    }
+
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn with_bailout(mut self, bailout: f64) -> Self {
+        self.bailout = bailout;
+        self
+    }
+}
+
+impl Sdf for QuaternionJulia {
+    fn distance(&self, point: DVec3) -> f64 {
+        let mut z = point.extend(self.slice);
+        // Squared length of the running derivative
+        let mut dz2 = 1.0;
+
+        for _ in 0..self.iterations {
+            dz2 *= 4.0 * z.length_squared();
+            z = square(z) + self.c;
+
+            if z.length_squared() > self.bailout * self.bailout {
+                break;
+            }
+        }
+
+        let r = z.length();
+        0.5 * r * r.ln() / dz2.sqrt()
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // Anything further than this from the origin escapes
+        let c = self.c.length();
+        let radius = (1.0 + (1.0 + 4.0 * c).sqrt()) / 2.0;
+        Aabb::new(DVec3::splat(-radius), DVec3::splat(radius))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{dvec3, dvec4};
+
+    use super::*;
+
+    fn julia() -> QuaternionJulia {
+        QuaternionJulia::new(dvec4(-0.2, 0.6, 0.2, 0.2))
+    }
+
+    #[test]
+    fn near_origin_is_inside() {
+        assert!(julia().distance(dvec3(0.1, 0.0, 0.0)) <= 0.0);
+    }
+
+    #[test]
+    fn bounds_contain_set() {
+        let julia = julia();
+        let bounds = julia.bounding_box();
+
+        for i in 0..=24 {
+            for j in 0..=24 {
+                for k in 0..=24 {
+                    let point = dvec3(i as f64, j as f64, k as f64) * 0.25 - 3.0;
+
+                    if bounds.grow(point) != bounds {
+                        assert!(julia.distance(point) > 0.0, "{point}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn iterations_and_bailout_matter() {
+        // Inside the orbit never escapes so only iterations change it
+        let inside = dvec3(0.1, 0.0, 0.0);
+        assert_ne!(
+            julia().with_iterations(3).distance(inside),
+            julia().distance(inside)
+        );
+
+        let outside = dvec3(2.0, 0.0, 0.0);
+        assert_ne!(
+            julia().with_bailout(2.0).distance(outside),
+            julia().distance(outside)
+        );
+    }
+}
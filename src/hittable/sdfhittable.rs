@@ -0,0 +1,167 @@
+use std::sync::Arc;
+
+use glam::{dvec3, DVec2, DVec3};
+
+use super::aabb::Aabb;
+use super::hit::Hit;
+use super::traits::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+use crate::sdf::Sdf;
+
+// Renders any signed distance field by sphere tracing
+pub struct SdfHittable<S: Sdf> {
+    sdf: S,
+    material: Arc<dyn Material>,
+    max_steps: usize,
+    epsilon: f64,
+}
+
+impl<S: Sdf> SdfHittable<S> {
+    pub fn new(sdf: S, material: Arc<dyn Material>) -> Self {
+        SdfHittable {
+            sdf,
+            material,
+            max_steps: 128,
+            epsilon: 0.0002,
+        }
+    }
+
+    // More steps are needed for grazing rays and detailed fractals
+    pub fn with_max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    // Distance from the surface that counts as a hit
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+
+    pub fn sdf(&self) -> &S {
+        &self.sdf
+    }
+
+    // Gradient of the distance estimate using the tetrahedral technique,
+    // 4 samples rather than 6 for central differences
+    fn normal(&self, point: DVec3) -> DVec3 {
+        let h = self.epsilon / 2.0;
+
+        [
+            dvec3(1.0, -1.0, -1.0),
+            dvec3(-1.0, -1.0, 1.0),
+            dvec3(-1.0, 1.0, -1.0),
+            dvec3(1.0, 1.0, 1.0),
+        ]
+        .into_iter()
+        .map(|k| k * self.sdf.distance(point + k * h))
+        .sum::<DVec3>()
+        .normalize_or(DVec3::Y)
+    }
+}
+
+impl<S: Sdf> Hittable for SdfHittable<S> {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        // Distance estimates can overshoot far from fractals so only march
+        // inside the bounds
+        let (mut distance, max) = self.bounding_box().interval(ray, min, max)?;
+
+        // Scattered rays start on the surface so have to leave it first
+        let mut leaving = true;
+
+        for _ in 0..self.max_steps {
+            if distance >= max {
+                return None;
+            }
+
+            // Estimate is negative inside
+            let delta = self.sdf.distance(ray.at(distance)).abs();
+
+            if delta >= self.epsilon {
+                leaving = false;
+            } else if leaving {
+                distance += self.epsilon;
+                continue;
+            } else {
+                let point = ray.at(distance);
+                let outward_normal = self.normal(point);
+                let front_face = outward_normal.dot(ray.direction) < 0.0;
+
+                let mut hit = Hit::new(
+                    point,
+                    if front_face {
+                        outward_normal
+                    } else {
+                        -outward_normal
+                    },
+                    distance,
+                    front_face,
+                    DVec2::ZERO,
+                    self.material.clone(),
+                );
+                hit.trap = self.sdf.orbit_trap(point);
+
+                return Some(hit);
+            }
+
+            distance += delta;
+        }
+
+        None
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // Marching stops slightly outside the surface
+        self.sdf.bounding_box().pad(0.01)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::sdf::{Cuboid, SdfExt, Sphere};
+    use crate::vec::approx_eq;
+
+    fn material() -> Arc<dyn Material> {
+        Arc::new(Lambertian::new(DVec3::ONE))
+    }
+
+    #[test]
+    fn hits_sphere() {
+        let sphere = SdfHittable::new(
+            Sphere::new(1.0).translate(dvec3(0.0, 0.0, -5.0)),
+            material(),
+        );
+        let ray = Ray::new(DVec3::ZERO, dvec3(0.0, 0.0, -1.0));
+        let hit = sphere.hit(&ray, 1e-9, f64::MAX).unwrap();
+
+        assert!((hit.distance - 4.0).abs() < 1e-3);
+        assert!(approx_eq(hit.normal, dvec3(0.0, 0.0, 1.0)));
+        assert!(hit.front_face);
+    }
+
+    #[test]
+    fn leaves_surface_before_hitting() {
+        let cuboid = SdfHittable::new(Cuboid::new(DVec3::ONE), material());
+        let ray = Ray::new(dvec3(0.0, 0.0, 1.0), dvec3(0.0, 0.0, -1.0));
+        let hit = cuboid.hit(&ray, 1e-9, f64::MAX).unwrap();
+
+        // Passes through the box to the far side
+        assert!((hit.distance - 2.0).abs() < 1e-3);
+        assert!(!hit.front_face);
+        assert!(approx_eq(hit.normal, dvec3(0.0, 0.0, 1.0)));
+    }
+
+    #[test]
+    fn stops_at_max() {
+        let sphere = SdfHittable::new(
+            Sphere::new(1.0).translate(dvec3(0.0, 0.0, -5.0)),
+            material(),
+        );
+        let ray = Ray::new(DVec3::ZERO, dvec3(0.0, 0.0, -1.0));
+
+        assert!(sphere.hit(&ray, 1e-9, 3.0).is_none());
+    }
+}
@@ -0,0 +1,97 @@
+use glam::DVec3;
+
+use super::aabb::Aabb;
+use crate::sdf::Sdf;
+
+// Regular tetrahedron with corners (1, 1, 1), (-1, -1, 1), (1, -1, -1) and
+// (-1, 1, -1), a lower bound on the distance outside
+fn tetrahedron(p: DVec3) -> f64 {
+    let d = (-p.x - p.y - p.z)
+        .max(p.x + p.y - p.z)
+        .max(-p.x + p.y + p.z)
+        .max(p.x - p.y + p.z);
+    (d - 1.0) / 3.0f64.sqrt()
+}
+
+// Sierpinski tetrahedron built by folding space, render with SdfHittable
+pub struct Sierpinski {
+    iterations: usize,
+    bailout: f64,
+}
+
+impl Sierpinski {
+    pub fn new() -> Self {
+        Sierpinski {
+            iterations: 12,
+            bailout: 100.0,
+        }
+    }
+
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+
+    pub fn with_bailout(mut self, bailout: f64) -> Self {
+        self.bailout = bailout;
+        self
+    }
+}
+
+impl Default for Sierpinski {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Sdf for Sierpinski {
+    fn distance(&self, point: DVec3) -> f64 {
+        let mut z = point;
+        let mut scale = 1.0;
+
+        for _ in 0..self.iterations {
+            // Reflect towards the (1, 1, 1) corner
+            if z.x + z.y < 0.0 {
+                (z.x, z.y) = (-z.y, -z.x);
+            }
+            if z.x + z.z < 0.0 {
+                (z.x, z.z) = (-z.z, -z.x);
+            }
+            if z.y + z.z < 0.0 {
+                (z.y, z.z) = (-z.z, -z.y);
+            }
+
+            // Grow that corner's half size copy back to full size
+            z = z * 2.0 - DVec3::ONE;
+            scale *= 2.0;
+
+            if z.length_squared() > self.bailout * self.bailout {
+                break;
+            }
+        }
+
+        tetrahedron(z) / scale
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(-DVec3::ONE, DVec3::ONE)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+
+    #[test]
+    fn edges_solid_centre_empty() {
+        let sierpinski = Sierpinski::new();
+
+        // Corners and the edges between them survive every iteration
+        assert!(sierpinski.distance(dvec3(1.0, 1.0, 1.0)) <= 1e-9);
+        assert!(sierpinski.distance(dvec3(0.0, 0.0, 1.0)) <= 1e-9);
+        assert!(sierpinski.distance(DVec3::ZERO) > 0.0);
+        assert!(sierpinski.distance(dvec3(2.0, 2.0, 2.0)) > 0.0);
+    }
+}
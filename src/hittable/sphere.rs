@@ -1,16 +1,21 @@
+use std::f64::consts::{PI, TAU};
 use std::sync::Arc;
 
-use glam::DVec3;
+use glam::{dvec2, dvec3, DVec2, DVec3};
 
+use super::aabb::Aabb;
 use super::hit::Hit;
 use super::traits::Hittable;
 use crate::material::Material;
+use crate::random::random_unit_vector;
 use crate::ray::Ray;
 
 pub struct Sphere {
     position: DVec3,
     radius: f64,
     material: Arc<dyn Material>,
+    // Distance moved between time 0 and 1
+    motion: DVec3,
 }
 
 impl Sphere {
@@ -19,13 +24,24 @@ impl Sphere {
             position,
             radius,
             material,
+            motion: DVec3::ZERO,
         }
     }
-}
 
-impl Hittable for Sphere {
-    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
-        let tmp = self.position - ray.origin;
+    // Moves in a straight line from position at time 0 to end at time 1
+    pub fn with_motion(mut self, end: DVec3) -> Self {
+        self.motion = end - self.position;
+        self
+    }
+
+    // Held at the ends outside the shutter interval bounding_box covers
+    fn centre(&self, time: f64) -> DVec3 {
+        self.position + self.motion * time.clamp(0.0, 1.0)
+    }
+
+    // Both distances where the ray's line crosses the sphere, nearest first
+    fn roots(&self, ray: &Ray) -> Option<[f64; 2]> {
+        let tmp = self.centre(ray.time) - ray.origin;
 
         // The quadratic equation but 2 is factored out
         let a = ray.direction.length_squared();
@@ -41,23 +57,135 @@ impl Hittable for Sphere {
 
         // 1st root will **always** be smaller
         let sqrtd = discriminant.sqrt();
-        let roots = [(h - sqrtd) / a, (h + sqrtd) / a];
-
-        roots.iter().find(|x| (min..max).contains(x)).map(|&x| {
-            let point = ray.at(x);
-            let outward_normal = (point - self.position).normalize();
-            let front_face = outward_normal.dot(ray.direction) < 0.0;
-            Hit {
-                point,
-                distance: x,
-                normal: if front_face {
-                    outward_normal
-                } else {
-                    -outward_normal
-                },
-                front_face,
-                material: self.material.clone(),
-            }
-        })
+        Some([(h - sqrtd) / a, (h + sqrtd) / a])
+    }
+
+    fn hit_at(&self, ray: &Ray, distance: f64) -> Hit {
+        let point = ray.at(distance);
+        let outward_normal = (point - self.centre(ray.time)).normalize();
+        let front_face = outward_normal.dot(ray.direction) < 0.0;
+        Hit {
+            point,
+            distance,
+            normal: if front_face {
+                outward_normal
+            } else {
+                -outward_normal
+            },
+            front_face,
+            uv: sphere_uv(outward_normal),
+            material: self.material.clone(),
+            trap: None,
+        }
+    }
+}
+
+// Longitude and latitude of a point on the unit sphere, v = 0 at the bottom
+fn sphere_uv(point: DVec3) -> DVec2 {
+    let theta = (-point.y).clamp(-1.0, 1.0).acos();
+    let phi = (-point.z).atan2(point.x) + PI;
+    dvec2(phi / TAU, theta / PI)
+}
+
+impl Hittable for Sphere {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        self.roots(ray)?
+            .into_iter()
+            .find(|x| (min..max).contains(x))
+            .map(|x| self.hit_at(ray, x))
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<(Hit, Hit)> {
+        match self.roots(ray) {
+            Some([entry, exit]) => vec![(self.hit_at(ray, entry), self.hit_at(ray, exit))],
+            None => vec![],
+        }
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // Covers the whole motion
+        let end = self.centre(1.0);
+        Aabb::new(self.position - self.radius, self.position + self.radius)
+            .union(&Aabb::new(end - self.radius, end + self.radius))
+    }
+
+    fn pdf(&self, origin: DVec3, direction: DVec3, time: f64) -> f64 {
+        let ray = Ray::new(origin, direction).with_time(time);
+
+        if self.hit(&ray, 1e-9, f64::MAX).is_none() {
+            return 0.0;
+        }
+
+        let distance_squared = (self.centre(time) - origin).length_squared();
+        let radius_squared = self.radius * self.radius;
+
+        // Inside the sphere every direction is sampled uniformly
+        if distance_squared <= radius_squared {
+            return 1.0 / (4.0 * PI);
+        }
+
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+        let solid_angle = 2.0 * PI * (1.0 - cos_theta_max);
+
+        1.0 / solid_angle
+    }
+
+    // Uniformly samples the cone of directions the sphere covers
+    fn sample_direction(&self, origin: DVec3, time: f64) -> Option<DVec3> {
+        let direction = self.centre(time) - origin;
+        let distance_squared = direction.length_squared();
+        let radius_squared = self.radius * self.radius;
+
+        if distance_squared <= radius_squared {
+            return Some(random_unit_vector());
+        }
+
+        let cos_theta_max = (1.0 - radius_squared / distance_squared).sqrt();
+
+        let z = 1.0 + fastrand::f64() * (cos_theta_max - 1.0);
+        let phi = 2.0 * PI * fastrand::f64();
+        let r = (1.0 - z * z).sqrt();
+
+        let w = direction.normalize();
+        let (u, v) = w.any_orthonormal_pair();
+        let local = dvec3(r * phi.cos(), r * phi.sin(), z);
+
+        Some(local.x * u + local.y * v + local.z * w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::material::Lambertian;
+
+    fn moving() -> Sphere {
+        Sphere::new(DVec3::ZERO, 1.0, Arc::new(Lambertian::new(DVec3::ONE)))
+            .with_motion(dvec3(4.0, 0.0, 0.0))
+    }
+
+    #[test]
+    fn motion_is_held_outside_shutter() {
+        let sphere = moving();
+        let bounds = sphere.bounding_box();
+
+        for time in [-1.0, 2.0] {
+            let centre = sphere.centre(time);
+            assert_eq!(bounds.grow(centre), bounds, "{time}");
+        }
+        assert_eq!(sphere.centre(3.0), dvec3(4.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn sampled_as_light_at_time() {
+        let sphere = moving();
+        let from = dvec3(4.0, 0.0, 5.0);
+
+        for _ in 0..20 {
+            let direction = sphere.sample_direction(from, 1.0).unwrap();
+
+            assert!(sphere.pdf(from, direction, 1.0) > 0.0);
+            assert_eq!(sphere.pdf(from, direction, 0.0), 0.0);
+        }
     }
 }
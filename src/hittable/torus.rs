@@ -0,0 +1,143 @@
+use std::f64::consts::TAU;
+use std::sync::Arc;
+
+use glam::{dvec2, DVec2, DVec3, Vec3Swizzles};
+
+use super::aabb::Aabb;
+use super::csg::pair_crossings;
+use super::hit::Hit;
+use super::polynomial::roots_between;
+use super::traits::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+
+// Ring around the y axis in the xz plane like the Torus SDF
+pub struct Torus {
+    major_radius: f64,
+    minor_radius: f64,
+    material: Arc<dyn Material>,
+}
+
+impl Torus {
+    pub fn new(major_radius: f64, minor_radius: f64, material: Arc<dyn Material>) -> Self {
+        Torus {
+            major_radius,
+            minor_radius,
+            material,
+        }
+    }
+
+    // Every crossing of the surface along the ray's line, nearest first
+    fn crossings(&self, ray: &Ray) -> Vec<Hit> {
+        let (major, minor) = (self.major_radius, self.minor_radius);
+
+        // Solve from the point nearest the centre so the coefficients stay
+        // small for far away rays, every root is then within major + minor
+        let shift = -ray.origin.dot(ray.direction);
+        let (o, d) = (ray.at(shift), ray.direction);
+
+        // (|p|^2 + R^2 - r^2)^2 = 4 R^2 (x^2 + z^2) with p = o + t d, |d| = 1
+        let n = o.dot(d);
+        let k = o.length_squared() + major * major - minor * minor;
+        let four_r2 = 4.0 * major * major;
+
+        let coefficients = [
+            k * k - four_r2 * o.xz().length_squared(),
+            4.0 * n * k - 2.0 * four_r2 * o.xz().dot(d.xz()),
+            4.0 * n * n + 2.0 * k - four_r2 * d.xz().length_squared(),
+            4.0 * n,
+            1.0,
+        ];
+
+        let reach = (major + minor) * 1.01;
+
+        roots_between(&coefficients, -reach, reach)
+            .into_iter()
+            .map(|t| {
+                let point = o + t * d;
+
+                // Away from the nearest point on the centre circle
+                let around = point.xz().normalize_or(DVec2::X);
+                let centre = DVec3::new(around.x, 0.0, around.y) * major;
+                let normal = (point - centre).normalize();
+
+                let u = (-point.z).atan2(point.x).rem_euclid(TAU) / TAU;
+                let v = normal.y.atan2(normal.xz().dot(around)).rem_euclid(TAU) / TAU;
+
+                Hit::from_outward_normal(ray, t + shift, normal, dvec2(u, v), self.material.clone())
+            })
+            .collect()
+    }
+}
+
+impl Hittable for Torus {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        // Cheap rejection, the quartic is comparatively slow
+        self.bounding_box().interval(ray, min, max)?;
+
+        self.crossings(ray)
+            .into_iter()
+            .find(|hit| (min..max).contains(&hit.distance))
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<(Hit, Hit)> {
+        pair_crossings(self.crossings(ray))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = self.major_radius + self.minor_radius;
+        let extent = DVec3::new(r, self.minor_radius, r);
+        Aabb::new(-extent, extent)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec::approx_eq;
+
+    fn torus() -> Torus {
+        Torus::new(2.0, 0.5, Arc::new(Lambertian::new(DVec3::ONE)))
+    }
+
+    #[test]
+    fn through_both_sides_of_the_ring() {
+        let ray = Ray::new(dvec3(-100.0, 0.0, 0.0), DVec3::X);
+        let intervals = torus().intervals(&ray);
+
+        let distances: Vec<(f64, f64)> = intervals
+            .iter()
+            .map(|(entry, exit)| (entry.distance, exit.distance))
+            .collect();
+        let expected = [(97.5, 98.5), (101.5, 102.5)];
+
+        assert_eq!(distances.len(), 2);
+        for (actual, expected) in distances.iter().zip(expected) {
+            assert!((actual.0 - expected.0).abs() < 1e-9);
+            assert!((actual.1 - expected.1).abs() < 1e-9);
+        }
+
+        // Exiting into the hole the surface faces the centre
+        let (entry, exit) = &intervals[0];
+        assert!(entry.front_face && !exit.front_face);
+        assert!(approx_eq(entry.normal, -DVec3::X));
+        assert!(approx_eq(exit.normal, -DVec3::X));
+    }
+
+    #[test]
+    fn top_of_the_tube() {
+        let ray = Ray::new(dvec3(0.0, 3.0, 2.0), -DVec3::Y);
+        let hit = torus().hit(&ray, 1e-9, f64::MAX).unwrap();
+
+        assert!((hit.distance - 2.5).abs() < 1e-9);
+        assert!(approx_eq(hit.normal, DVec3::Y));
+        assert!((hit.uv.y - 0.25).abs() < 1e-9);
+
+        assert!(torus()
+            .hit(&Ray::new(dvec3(0.0, 3.0, 0.0), -DVec3::Y), 1e-9, f64::MAX)
+            .is_none());
+    }
+}
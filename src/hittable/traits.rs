@@ -1,6 +1,56 @@
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use super::aabb::Aabb;
 use super::hit::Hit;
 use crate::ray::Ray;
 
 pub trait Hittable: Send + Sync {
     fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit>;
+
+    fn bounding_box(&self) -> Aabb;
+
+    // Every span of the line through the ray that is inside the object, as
+    // (entry, exit) pairs sorted by distance. Distances can be negative so
+    // CSG knows whether the ray starts inside. Normals face against the ray
+    // like hit's. Empty for objects that aren't closed
+    fn intervals(&self, _ray: &Ray) -> Vec<(Hit, Hit)> {
+        vec![]
+    }
+
+    // Solid angle pdf of sample_direction choosing direction from origin, with
+    // moving objects where they are at time
+    fn pdf(&self, _origin: DVec3, _direction: DVec3, _time: f64) -> f64 {
+        0.0
+    }
+
+    // Unnormalised direction from origin towards a random point on the object
+    // None if the object can't be used as a light
+    fn sample_direction(&self, _origin: DVec3, _time: f64) -> Option<DVec3> {
+        None
+    }
+}
+
+// Lets shared objects be wrapped, e.g. one mesh in many Transformed instances
+impl<H: Hittable + ?Sized> Hittable for Arc<H> {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        (**self).hit(ray, min, max)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<(Hit, Hit)> {
+        (**self).intervals(ray)
+    }
+
+    fn pdf(&self, origin: DVec3, direction: DVec3, time: f64) -> f64 {
+        (**self).pdf(origin, direction, time)
+    }
+
+    fn sample_direction(&self, origin: DVec3, time: f64) -> Option<DVec3> {
+        (**self).sample_direction(origin, time)
+    }
 }
@@ -0,0 +1,332 @@
+use glam::{DAffine3, DMat3, DQuat, DVec3};
+
+use super::aabb::Aabb;
+use super::hit::Hit;
+use super::traits::Hittable;
+use crate::ray::Ray;
+
+// Steps checked between keyframes when bounding the motion, rotations can
+// swing objects outside the boxes at either end
+const BOUNDS_STEPS: usize = 8;
+
+#[derive(Clone, Copy)]
+struct Frame {
+    matrix: DAffine3,
+    inverse: DAffine3,
+    // Inverse transpose, keeps normals perpendicular under non-uniform scale
+    normal_matrix: DMat3,
+}
+
+impl Frame {
+    fn new(matrix: DAffine3) -> Self {
+        let inverse = matrix.inverse();
+
+        Frame {
+            matrix,
+            inverse,
+            normal_matrix: inverse.matrix3.transpose(),
+        }
+    }
+}
+
+struct Keyframe {
+    time: f64,
+    scale: DVec3,
+    rotation: DQuat,
+    translation: DVec3,
+}
+
+// Places an object in the world with an affine transform, wrap an Arc to
+// instance one object many times
+pub struct Transformed<H: Hittable> {
+    object: H,
+    frame: Frame,
+    // Sorted by time, empty unless animated
+    keyframes: Vec<Keyframe>,
+}
+
+impl<H: Hittable> Transformed<H> {
+    pub fn new(object: H, matrix: DAffine3) -> Self {
+        Transformed {
+            object,
+            frame: Frame::new(matrix),
+            keyframes: vec![],
+        }
+    }
+
+    // Interpolates between (time, transform) pairs by ray time and holds the
+    // first and last outside them. Transforms are split into scale, rotation
+    // and translation to interpolate so any shear is lost
+    pub fn keyframed(object: H, mut keyframes: Vec<(f64, DAffine3)>) -> Self {
+        assert!(!keyframes.is_empty());
+        keyframes.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let keyframes: Vec<Keyframe> = keyframes
+            .into_iter()
+            .map(|(time, matrix)| {
+                let (scale, rotation, translation) = matrix.to_scale_rotation_translation();
+                Keyframe {
+                    time,
+                    scale,
+                    rotation,
+                    translation,
+                }
+            })
+            .collect();
+
+        let mut out = Transformed {
+            object,
+            frame: Frame::new(DAffine3::IDENTITY),
+            keyframes,
+        };
+        out.frame = out.frame_at(0.0);
+        out
+    }
+
+    // Object space copy of the ray and how much longer its distances are
+    fn to_local(frame: &Frame, ray: &Ray) -> (Ray, f64) {
+        let direction = frame.inverse.transform_vector3(ray.direction);
+
+        // Object space rays are normalised too, so distances are scaled
+        let scale = direction.length();
+        let local =
+            Ray::new(frame.inverse.transform_point3(ray.origin), direction).with_time(ray.time);
+
+        (local, scale)
+    }
+
+    fn to_world(frame: &Frame, mut hit: Hit, scale: f64) -> Hit {
+        hit.point = frame.matrix.transform_point3(hit.point);
+        hit.normal = (frame.normal_matrix * hit.normal).normalize();
+        hit.distance /= scale;
+        hit
+    }
+
+    fn frame_at(&self, time: f64) -> Frame {
+        if self.keyframes.is_empty() {
+            return self.frame;
+        }
+
+        let i = self.keyframes.partition_point(|k| k.time <= time);
+        let (a, b) = match i {
+            0 => (&self.keyframes[0], &self.keyframes[0]),
+            i if i == self.keyframes.len() => (&self.keyframes[i - 1], &self.keyframes[i - 1]),
+            i => (&self.keyframes[i - 1], &self.keyframes[i]),
+        };
+
+        let t = if b.time > a.time {
+            (time - a.time) / (b.time - a.time)
+        } else {
+            0.0
+        };
+
+        Frame::new(DAffine3::from_scale_rotation_translation(
+            a.scale.lerp(b.scale, t),
+            a.rotation.slerp(b.rotation, t),
+            a.translation.lerp(b.translation, t),
+        ))
+    }
+}
+
+impl<H: Hittable> Hittable for Transformed<H> {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        let frame = self.frame_at(ray.time);
+        let (local, scale) = Self::to_local(&frame, ray);
+
+        let hit = self.object.hit(&local, min * scale, max * scale)?;
+        Some(Self::to_world(&frame, hit, scale))
+    }
+
+    fn intervals(&self, ray: &Ray) -> Vec<(Hit, Hit)> {
+        let frame = self.frame_at(ray.time);
+        let (local, scale) = Self::to_local(&frame, ray);
+
+        self.object
+            .intervals(&local)
+            .into_iter()
+            .map(|(entry, exit)| {
+                (
+                    Self::to_world(&frame, entry, scale),
+                    Self::to_world(&frame, exit, scale),
+                )
+            })
+            .collect()
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let inner = self.object.bounding_box();
+
+        if self.keyframes.len() < 2 {
+            return inner.transform(&self.frame.matrix);
+        }
+
+        // Covers the whole motion. Rotation swings points along arcs between
+        // the sampled poses, which bulge past the chords by at most the sag
+        let reach = inner.min.abs().max(inner.max.abs());
+
+        self.keyframes.windows(2).fold(Aabb::EMPTY, |acc, pair| {
+            let (a, b) = (&pair[0], &pair[1]);
+            let step = a.rotation.angle_between(b.rotation) / BOUNDS_STEPS as f64;
+            let radius = (reach * a.scale.abs().max(b.scale.abs())).length();
+            let sag = radius * (1.0 - (step / 2.0).cos());
+
+            (0..=BOUNDS_STEPS)
+                .map(|i| a.time + (b.time - a.time) * i as f64 / BOUNDS_STEPS as f64)
+                .fold(acc, |acc, time| {
+                    acc.union(&inner.transform(&self.frame_at(time).matrix).pad(sag))
+                })
+        })
+    }
+
+    fn pdf(&self, origin: DVec3, direction: DVec3, time: f64) -> f64 {
+        let inverse = self.frame_at(time).inverse;
+        let direction = inverse.transform_vector3(direction.normalize());
+        let pdf = self
+            .object
+            .pdf(inverse.transform_point3(origin), direction, time);
+
+        // Jacobian of mapping world directions to object space directions
+        let det = inverse.matrix3.determinant().abs();
+        pdf * det / direction.length().powi(3)
+    }
+
+    fn sample_direction(&self, origin: DVec3, time: f64) -> Option<DVec3> {
+        let frame = self.frame_at(time);
+
+        self.object
+            .sample_direction(frame.inverse.transform_point3(origin), time)
+            .map(|direction| frame.matrix.transform_vector3(direction))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::FRAC_PI_4;
+    use std::sync::Arc;
+
+    use glam::{dvec3, DQuat};
+
+    use super::*;
+    use crate::hittable::{Quad, Sphere};
+    use crate::material::{Lambertian, Material};
+    use crate::vec::approx_eq;
+
+    fn material() -> Arc<dyn Material> {
+        Arc::new(Lambertian::new(DVec3::ONE))
+    }
+
+    #[test]
+    fn ellipsoid_normal() {
+        // Sphere squashed to half height
+        let ellipsoid = Transformed::new(
+            Sphere::new(DVec3::ZERO, 1.0, material()),
+            DAffine3::from_scale(dvec3(1.0, 0.5, 1.0)),
+        );
+
+        let ray = Ray::new(dvec3(0.0, 5.0, 0.0), dvec3(0.0, -1.0, 0.0));
+        let hit = ellipsoid.hit(&ray, 1e-9, f64::MAX).unwrap();
+        assert!((hit.distance - 4.5).abs() < 1e-9);
+        assert!(approx_eq(hit.point, dvec3(0.0, 0.5, 0.0)));
+
+        // At 45 degrees on the unit sphere the ellipse normal tilts upwards
+        let ray = Ray::new(
+            dvec3(5.0, FRAC_PI_4.sin() * 0.5, 0.0),
+            dvec3(-1.0, 0.0, 0.0),
+        );
+        let hit = ellipsoid.hit(&ray, 1e-9, f64::MAX).unwrap();
+        assert!(approx_eq(hit.normal, dvec3(1.0, 2.0, 0.0).normalize()));
+    }
+
+    #[test]
+    fn matches_world_space_quad() {
+        let matrix = DAffine3::from_scale_rotation_translation(
+            dvec3(2.0, 0.5, 3.0),
+            DQuat::from_rotation_x(0.3) * DQuat::from_rotation_y(0.7),
+            dvec3(1.0, 4.0, -2.0),
+        );
+        let (origin, u, v) = (
+            dvec3(-1.0, 0.0, -1.0),
+            dvec3(2.0, 0.0, 0.0),
+            dvec3(0.0, 0.5, 2.0),
+        );
+
+        let shared: Arc<dyn Hittable> = Arc::new(Quad::new(origin, u, v, material()));
+        let instance = Transformed::new(shared, matrix);
+        let world = Quad::new(
+            matrix.transform_point3(origin),
+            matrix.transform_vector3(u),
+            matrix.transform_vector3(v),
+            material(),
+        );
+
+        let from = dvec3(0.5, -3.0, 1.0);
+
+        for _ in 0..20 {
+            let direction = world.sample_direction(from, 0.0).unwrap();
+            let ray = Ray::new(from, direction);
+
+            let expected = world.hit(&ray, 1e-9, f64::MAX).unwrap();
+            let actual = instance.hit(&ray, 1e-9, f64::MAX).unwrap();
+
+            assert!((actual.distance - expected.distance).abs() < 1e-9);
+            assert!(approx_eq(actual.point, expected.point));
+            assert!(approx_eq(actual.normal, expected.normal));
+            assert!((actual.uv - expected.uv).length() < 1e-9);
+            assert_eq!(actual.front_face, expected.front_face);
+
+            let (expected, actual) = (
+                world.pdf(from, direction, 0.0),
+                instance.pdf(from, direction, 0.0),
+            );
+            assert!((actual - expected).abs() < 1e-9 * expected);
+        }
+    }
+
+    #[test]
+    fn keyframes_interpolate() {
+        let sphere = Sphere::new(DVec3::ZERO, 1.0, material());
+        let moving = Transformed::keyframed(
+            sphere,
+            vec![
+                (1.0, DAffine3::from_translation(dvec3(4.0, 0.0, 0.0))),
+                (0.0, DAffine3::IDENTITY),
+            ],
+        );
+
+        let ray = |time| Ray::new(dvec3(2.0, 0.0, 5.0), dvec3(0.0, 0.0, -1.0)).with_time(time);
+        assert!(moving.hit(&ray(0.0), 1e-9, f64::MAX).is_none());
+        assert!(moving.hit(&ray(0.5), 1e-9, f64::MAX).is_some());
+        assert!(moving.hit(&ray(2.0), 1e-9, f64::MAX).is_none());
+
+        // Sampled as a light where it is at the ray's time
+        let from = dvec3(2.0, 0.0, 5.0);
+        let direction = moving.sample_direction(from, 0.5).unwrap();
+        assert!(moving.pdf(from, direction, 0.5) > 0.0);
+        assert_eq!(moving.pdf(from, direction, 0.0), 0.0);
+
+        let bounds = moving.bounding_box();
+        assert!(approx_eq(bounds.min, dvec3(-1.0, -1.0, -1.0)));
+        assert!(approx_eq(bounds.max, dvec3(5.0, 1.0, 1.0)));
+    }
+
+    #[test]
+    fn rotating_bounds_cover_arc() {
+        // Off centre so it sweeps a wide arc, the extremes fall between samples
+        let sphere = Sphere::new(dvec3(3.0, 0.0, 0.0), 0.5, material());
+        let spinning = Transformed::keyframed(
+            sphere,
+            vec![
+                (0.0, DAffine3::IDENTITY),
+                (1.0, DAffine3::from_rotation_y(170f64.to_radians())),
+            ],
+        );
+
+        let bounds = spinning.bounding_box();
+        let inner = Aabb::new(dvec3(2.5, -0.5, -0.5), dvec3(3.5, 0.5, 0.5));
+
+        for i in 0..=1000 {
+            let pose = inner.transform(&spinning.frame_at(i as f64 / 1000.0).matrix);
+            assert_eq!(bounds.union(&pose), bounds, "{i}");
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use std::sync::Arc;
+
+use glam::{dvec2, DVec2, DVec3};
+
+use super::aabb::Aabb;
+use super::hit::Hit;
+use super::traits::Hittable;
+use crate::material::Material;
+use crate::ray::Ray;
+
+// Möller–Trumbore, returns distance and barycentric weights of vertices 1 and 2
+pub(super) fn intersect(
+    ray: &Ray,
+    [a, b, c]: [DVec3; 3],
+    min: f64,
+    max: f64,
+) -> Option<(f64, f64, f64)> {
+    let edge1 = b - a;
+    let edge2 = c - a;
+
+    let p = ray.direction.cross(edge2);
+    let determinant = edge1.dot(p);
+
+    // Ray is parallel to the triangle
+    if determinant.abs() < 1e-12 {
+        return None;
+    }
+
+    let inv_determinant = 1.0 / determinant;
+    let s = ray.origin - a;
+
+    let u = s.dot(p) * inv_determinant;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let q = s.cross(edge1);
+    let v = ray.direction.dot(q) * inv_determinant;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+
+    let distance = edge2.dot(q) * inv_determinant;
+
+    (min..max).contains(&distance).then_some((distance, u, v))
+}
+
+// Triangles without uvs use their barycentric coordinates, so textures still
+// vary across them
+pub(super) const DEFAULT_UVS: [DVec2; 3] = [dvec2(0.0, 0.0), dvec2(1.0, 0.0), dvec2(0.0, 1.0)];
+
+// Builds the hit record shared by triangles and meshes
+pub(super) fn shade(
+    ray: &Ray,
+    (distance, u, v): (f64, f64, f64),
+    [a, b, c]: [DVec3; 3],
+    normals: Option<[DVec3; 3]>,
+    [uv_a, uv_b, uv_c]: [DVec2; 3],
+    material: &Arc<dyn Material>,
+) -> Hit {
+    let w = 1.0 - u - v;
+    let geometric_normal = (b - a).cross(c - a).normalize();
+
+    // Shading normal is put on the same side as the geometric normal
+    let shading_normal = match normals {
+        Some([n_a, n_b, n_c]) => {
+            let n = (w * n_a + u * n_b + v * n_c).normalize_or(geometric_normal);
+
+            if n.dot(geometric_normal) < 0.0 {
+                -n
+            } else {
+                n
+            }
+        }
+        None => geometric_normal,
+    };
+
+    let front_face = geometric_normal.dot(ray.direction) < 0.0;
+
+    Hit::new(
+        ray.at(distance),
+        if front_face {
+            shading_normal
+        } else {
+            -shading_normal
+        },
+        distance,
+        front_face,
+        w * uv_a + u * uv_b + v * uv_c,
+        material.clone(),
+    )
+}
+
+pub struct Triangle {
+    vertices: [DVec3; 3],
+    normals: Option<[DVec3; 3]>,
+    uvs: [DVec2; 3],
+    material: Arc<dyn Material>,
+}
+
+impl Triangle {
+    pub fn new(a: DVec3, b: DVec3, c: DVec3, material: Arc<dyn Material>) -> Self {
+        Triangle {
+            vertices: [a, b, c],
+            normals: None,
+            uvs: DEFAULT_UVS,
+            material,
+        }
+    }
+
+    // Per-vertex normals for smooth shading
+    pub fn with_normals(mut self, normals: [DVec3; 3]) -> Self {
+        self.normals = Some(normals.map(DVec3::normalize));
+        self
+    }
+
+    pub fn with_uvs(mut self, uvs: [DVec2; 3]) -> Self {
+        self.uvs = uvs;
+        self
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        intersect(ray, self.vertices, min, max).map(|x| {
+            shade(
+                ray,
+                x,
+                self.vertices,
+                self.normals,
+                self.uvs,
+                &self.material,
+            )
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // Axis aligned triangles would have no thickness
+        Aabb::from_points(self.vertices).pad(1e-9)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec::approx_eq;
+
+    fn triangle() -> Triangle {
+        Triangle::new(
+            dvec3(0.0, 0.0, 0.0),
+            dvec3(1.0, 0.0, 0.0),
+            dvec3(0.0, 1.0, 0.0),
+            Arc::new(Lambertian::new(DVec3::ONE)),
+        )
+    }
+
+    #[test]
+    fn hit_front_face() {
+        let ray = Ray::new(dvec3(0.25, 0.25, 1.0), dvec3(0.0, 0.0, -1.0));
+        let hit = triangle().hit(&ray, 1e-9, f64::MAX).unwrap();
+
+        assert!(hit.front_face);
+        assert!((hit.distance - 1.0).abs() < 1e-9);
+        assert!(approx_eq(hit.point, dvec3(0.25, 0.25, 0.0)));
+        assert!(approx_eq(hit.normal, dvec3(0.0, 0.0, 1.0)));
+        assert!((hit.uv - dvec2(0.25, 0.25)).length() < 1e-9);
+    }
+
+    #[test]
+    fn hit_back_face() {
+        let ray = Ray::new(dvec3(0.25, 0.25, -1.0), dvec3(0.0, 0.0, 1.0));
+        let hit = triangle().hit(&ray, 1e-9, f64::MAX).unwrap();
+
+        assert!(!hit.front_face);
+        assert!(approx_eq(hit.normal, dvec3(0.0, 0.0, -1.0)));
+    }
+
+    #[test]
+    fn miss_outside_edges() {
+        let ray = Ray::new(dvec3(0.75, 0.75, 1.0), dvec3(0.0, 0.0, -1.0));
+
+        assert!(triangle().hit(&ray, 1e-9, f64::MAX).is_none());
+    }
+
+    #[test]
+    fn interpolate_normals() {
+        let n = dvec3(0.0, 0.0, 1.0);
+        let tilted = dvec3(1.0, 0.0, 1.0);
+        let triangle = triangle().with_normals([n, tilted, n]);
+
+        let ray = Ray::new(dvec3(0.5, 0.0, 1.0), dvec3(0.0, 0.0, -1.0));
+        let hit = triangle.hit(&ray, 1e-9, f64::MAX).unwrap();
+
+        let expected = (0.5 * n + 0.5 * tilted.normalize()).normalize();
+        assert!(approx_eq(hit.normal, expected));
+    }
+}
@@ -0,0 +1,268 @@
+use std::sync::Arc;
+
+use glam::{DVec2, DVec3};
+
+use super::aabb::Aabb;
+use super::bvh::Bvh;
+use super::hit::Hit;
+use super::traits::Hittable;
+use super::triangle::{intersect, shade, DEFAULT_UVS};
+use crate::material::Material;
+use crate::ray::Ray;
+
+// Indices into the mesh buffers for each corner of a triangle
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Face {
+    pub positions: [usize; 3],
+    pub normals: Option<[usize; 3]>,
+    pub uvs: Option<[usize; 3]>,
+}
+
+impl Face {
+    pub fn new(positions: [usize; 3]) -> Self {
+        Face {
+            positions,
+            normals: None,
+            uvs: None,
+        }
+    }
+}
+
+struct MeshData {
+    positions: Vec<DVec3>,
+    normals: Vec<DVec3>,
+    uvs: Vec<DVec2>,
+    faces: Vec<Face>,
+    material: Arc<dyn Material>,
+}
+
+impl MeshData {
+    fn vertices(&self, face: &Face) -> [DVec3; 3] {
+        face.positions.map(|i| self.positions[i])
+    }
+}
+
+// A single face referring back into the shared buffers
+struct MeshTriangle {
+    mesh: Arc<MeshData>,
+    index: usize,
+}
+
+impl Hittable for MeshTriangle {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        let mesh = &self.mesh;
+        let face = &mesh.faces[self.index];
+        let vertices = mesh.vertices(face);
+
+        intersect(ray, vertices, min, max).map(|x| {
+            let normals = face.normals.map(|n| n.map(|i| mesh.normals[i]));
+            let uvs = face.uvs.map_or(DEFAULT_UVS, |uv| uv.map(|i| mesh.uvs[i]));
+
+            shade(ray, x, vertices, normals, uvs, &mesh.material)
+        })
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let face = &self.mesh.faces[self.index];
+        Aabb::from_points(self.mesh.vertices(face)).pad(1e-9)
+    }
+}
+
+pub struct TriangleMesh {
+    mesh: Arc<MeshData>,
+    bvh: Bvh,
+}
+
+impl TriangleMesh {
+    // Panics if a face refers to a vertex, normal or uv that doesn't exist
+    pub fn new(
+        positions: Vec<DVec3>,
+        normals: Vec<DVec3>,
+        uvs: Vec<DVec2>,
+        faces: Vec<Face>,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        for face in &faces {
+            assert!(face.positions.iter().all(|&i| i < positions.len()));
+            assert!(face
+                .normals
+                .is_none_or(|n| n.iter().all(|&i| i < normals.len())));
+            assert!(face.uvs.is_none_or(|uv| uv.iter().all(|&i| i < uvs.len())));
+        }
+
+        let mesh = Arc::new(MeshData {
+            positions,
+            normals: normals.into_iter().map(|n| n.normalize_or_zero()).collect(),
+            uvs,
+            faces,
+            material,
+        });
+
+        let triangles = (0..mesh.faces.len())
+            .map(|index| {
+                Arc::new(MeshTriangle {
+                    mesh: mesh.clone(),
+                    index,
+                }) as Arc<dyn Hittable>
+            })
+            .collect();
+
+        TriangleMesh {
+            bvh: Bvh::new(triangles),
+            mesh,
+        }
+    }
+
+    // Flat shaded mesh from positions and vertex indices
+    pub fn from_indices(
+        positions: Vec<DVec3>,
+        indices: Vec<[usize; 3]>,
+        material: Arc<dyn Material>,
+    ) -> Self {
+        let faces = indices.into_iter().map(Face::new).collect();
+        TriangleMesh::new(positions, vec![], vec![], faces, material)
+    }
+
+    // Convex polygon split into triangles around its first vertex
+    pub fn fan(positions: Vec<DVec3>, material: Arc<dyn Material>) -> Self {
+        assert!(positions.len() >= 3);
+
+        let indices = (1..positions.len() - 1).map(|i| [0, i, i + 1]).collect();
+        TriangleMesh::from_indices(positions, indices, material)
+    }
+
+    pub fn len(&self) -> usize {
+        self.mesh.faces.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.mesh.faces.is_empty()
+    }
+}
+
+impl Hittable for TriangleMesh {
+    fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        self.bvh.hit(ray, min, max)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.bvh.bounding_box()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{dvec2, dvec3};
+
+    use super::*;
+    use crate::hittable::Triangle;
+    use crate::material::Lambertian;
+    use crate::vec::{approx_eq, approx_eq_with_error};
+
+    // Unit square in the xy plane made of two triangles
+    fn square() -> TriangleMesh {
+        let positions = vec![
+            dvec3(0.0, 0.0, 0.0),
+            dvec3(1.0, 0.0, 0.0),
+            dvec3(1.0, 1.0, 0.0),
+            dvec3(0.0, 1.0, 0.0),
+        ];
+        let uvs = vec![
+            dvec2(0.0, 0.0),
+            dvec2(1.0, 0.0),
+            dvec2(1.0, 1.0),
+            dvec2(0.0, 1.0),
+        ];
+        let faces = vec![
+            Face {
+                positions: [0, 1, 2],
+                normals: None,
+                uvs: Some([0, 1, 2]),
+            },
+            Face {
+                positions: [0, 2, 3],
+                normals: None,
+                uvs: Some([0, 2, 3]),
+            },
+        ];
+
+        TriangleMesh::new(
+            positions,
+            vec![],
+            uvs,
+            faces,
+            Arc::new(Lambertian::new(DVec3::ONE)),
+        )
+    }
+
+    #[test]
+    fn hit_both_faces() {
+        let mesh = square();
+
+        for (x, y) in [(0.8, 0.2), (0.2, 0.8)] {
+            let ray = Ray::new(dvec3(x, y, 1.0), dvec3(0.0, 0.0, -1.0));
+            let hit = mesh.hit(&ray, 1e-9, f64::MAX).unwrap();
+
+            assert!(approx_eq(hit.point, dvec3(x, y, 0.0)));
+            assert!((hit.uv - dvec2(x, y)).length() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn missing_uvs_match_triangle() {
+        let a = dvec3(0.0, 0.0, 0.0);
+        let b = dvec3(2.0, 0.0, 0.0);
+        let c = dvec3(0.0, 2.0, 0.0);
+        let material: Arc<dyn Material> = Arc::new(Lambertian::new(DVec3::ONE));
+
+        let mesh = TriangleMesh::new(
+            vec![a, b, c],
+            vec![],
+            vec![],
+            vec![Face::new([0, 1, 2])],
+            material.clone(),
+        );
+        let triangle = Triangle::new(a, b, c, material);
+
+        let ray = Ray::new(dvec3(0.5, 1.0, 1.0), dvec3(0.0, 0.0, -1.0));
+        let mesh_uv = mesh.hit(&ray, 1e-9, f64::MAX).unwrap().uv;
+        let triangle_uv = triangle.hit(&ray, 1e-9, f64::MAX).unwrap().uv;
+
+        assert!((mesh_uv - dvec2(0.25, 0.5)).length() < 1e-9);
+        assert!((mesh_uv - triangle_uv).length() < 1e-9);
+    }
+
+    #[test]
+    fn bounds_cover_vertices() {
+        let bounds = square().bounding_box();
+
+        assert!(approx_eq_with_error(bounds.min, DVec3::ZERO, 1e-6));
+        assert!(approx_eq_with_error(bounds.max, dvec3(1.0, 1.0, 0.0), 1e-6));
+    }
+
+    #[test]
+    fn fan_covers_polygon() {
+        // Regular hexagon of radius 1 around the origin in the xy plane
+        let positions = (0..6)
+            .map(|i| {
+                let angle = i as f64 * std::f64::consts::TAU / 6.0;
+                dvec3(angle.cos(), angle.sin(), 0.0)
+            })
+            .collect();
+        let fan = TriangleMesh::fan(positions, Arc::new(Lambertian::new(DVec3::ONE)));
+
+        assert_eq!(fan.len(), 4);
+
+        for point in [
+            dvec3(0.0, 0.0, 1.0),
+            dvec3(-0.9, 0.0, 1.0),
+            dvec3(0.4, 0.7, 1.0),
+        ] {
+            let ray = Ray::new(point, dvec3(0.0, 0.0, -1.0));
+            assert!(fan.hit(&ray, 1e-9, f64::MAX).is_some());
+        }
+
+        let ray = Ray::new(dvec3(0.0, 0.95, 1.0), dvec3(0.0, 0.0, -1.0));
+        assert!(fan.hit(&ray, 1e-9, f64::MAX).is_none());
+    }
+}
@@ -1,6 +1,15 @@
 pub mod camera;
+pub mod distribution;
+pub mod environment;
 pub mod hittable;
+pub mod loader;
 pub mod material;
+pub mod output;
 pub mod random;
 pub mod ray;
+pub mod scene;
+pub mod sdf;
+pub mod texture;
+pub mod tonemap;
 pub mod vec;
+pub mod volume;
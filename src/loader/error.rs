@@ -0,0 +1,64 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io;
+use std::path::PathBuf;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    InvalidNumber(String),
+    InvalidIndex(String),
+    IndexOutOfRange(i64),
+    MissingArgument(&'static str),
+    TooFewVertices(usize),
+    UnknownMaterial(String),
+    Unsupported(String),
+    TooFewValues { expected: usize, found: usize },
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::InvalidNumber(x) => write!(f, "invalid number '{x}'"),
+            ParseError::InvalidIndex(x) => write!(f, "invalid vertex reference '{x}'"),
+            ParseError::IndexOutOfRange(x) => write!(f, "index {x} is out of range"),
+            ParseError::MissingArgument(x) => write!(f, "missing {x}"),
+            ParseError::TooFewVertices(x) => write!(f, "face has {x} vertices, needs at least 3"),
+            ParseError::UnknownMaterial(x) => write!(f, "material '{x}' is not defined"),
+            ParseError::Unsupported(x) => write!(f, "unsupported {x}"),
+            ParseError::TooFewValues { expected, found } => {
+                write!(f, "expected {expected} values, found {found}")
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(PathBuf, io::Error),
+    // Line numbers start at 1
+    Obj { line: usize, error: ParseError },
+    Mtl { line: usize, error: ParseError },
+    Nrrd { line: usize, error: ParseError },
+    Raw(ParseError),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Io(path, error) => write!(f, "failed to read {}: {error}", path.display()),
+            LoadError::Obj { line, error } => write!(f, "obj line {line}: {error}"),
+            LoadError::Mtl { line, error } => write!(f, "mtl line {line}: {error}"),
+            LoadError::Nrrd { line, error } => write!(f, "nrrd line {line}: {error}"),
+            LoadError::Raw(error) => write!(f, "raw volume: {error}"),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(_, error) => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+pub mod error;
+pub mod mtl;
+pub mod nrrd;
+pub mod obj;
+mod parse;
+
+pub use error::{LoadError, ParseError};
+pub use mtl::{load_mtl, parse_mtl};
+pub use nrrd::{load_nrrd, load_raw, parse_nrrd, parse_raw};
+pub use obj::{load_obj, parse_obj};
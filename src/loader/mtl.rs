@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use super::error::{LoadError, ParseError};
+use super::parse::{number, tokenize, vector};
+use crate::material::{Dielectric, Lambertian, Material, Metal};
+
+pub type Materials = HashMap<String, Arc<dyn Material>>;
+
+// Only the parameters that map onto our materials
+struct MtlMaterial {
+    diffuse: DVec3,
+    specular: DVec3,
+    // None when no Ns was given
+    shininess: Option<f64>,
+    refractive_index: f64,
+    dissolve: f64,
+    illum: u32,
+}
+
+impl Default for MtlMaterial {
+    fn default() -> Self {
+        MtlMaterial {
+            diffuse: DVec3::splat(0.8),
+            specular: DVec3::ZERO,
+            shininess: None,
+            refractive_index: 1.5,
+            dissolve: 1.0,
+            illum: 2,
+        }
+    }
+}
+
+impl MtlMaterial {
+    fn build(&self) -> Arc<dyn Material> {
+        // Illumination models 4, 6, 7 and 9 all involve transparency
+        if self.dissolve < 1.0 || matches!(self.illum, 4 | 6 | 7 | 9) {
+            return Arc::new(Dielectric::new(self.refractive_index));
+        }
+
+        // Models 3, 5 and 8 are ray traced reflections
+        let reflective = matches!(self.illum, 3 | 5 | 8);
+
+        if reflective || self.specular.max_element() > self.diffuse.max_element() {
+            // Roughness from the Phong exponent as in Walter et al., a perfect
+            // mirror without one
+            let fuzz = self
+                .shininess
+                .map_or(0.0, |shininess| (2.0 / (shininess + 2.0)).sqrt());
+
+            // Mirrors are often given only a Kd, which would make them black
+            let albedo = if self.specular == DVec3::ZERO {
+                self.diffuse
+            } else {
+                self.specular
+            };
+            return Arc::new(Metal::new(albedo, fuzz));
+        }
+
+        Arc::new(Lambertian::new(self.diffuse))
+    }
+}
+
+pub fn parse_mtl(source: &str) -> Result<Materials, LoadError> {
+    let mut out = Materials::new();
+    let mut current: Option<(String, MtlMaterial)> = None;
+
+    for (i, line) in source.lines().enumerate() {
+        let error = |error| LoadError::Mtl { line: i + 1, error };
+
+        let Some((keyword, mut args)) = tokenize(line) else {
+            continue;
+        };
+
+        if keyword == "newmtl" {
+            if let Some((name, material)) = current.take() {
+                out.insert(name, material.build());
+            }
+
+            let name = args
+                .next()
+                .ok_or(ParseError::MissingArgument("material name"))
+                .map_err(error)?;
+
+            current = Some((name.to_string(), MtlMaterial::default()));
+            continue;
+        }
+
+        // Parameters before the first newmtl are ignored
+        let Some((_, material)) = current.as_mut() else {
+            continue;
+        };
+
+        match keyword {
+            "Kd" => material.diffuse = vector(&mut args, "colour").map_err(error)?,
+            "Ks" => material.specular = vector(&mut args, "colour").map_err(error)?,
+            "Ns" => material.shininess = Some(number(args.next(), "exponent").map_err(error)?),
+            "Ni" => material.refractive_index = number(args.next(), "index").map_err(error)?,
+            "d" => material.dissolve = number(args.next(), "dissolve").map_err(error)?,
+            "Tr" => material.dissolve = 1.0 - number(args.next(), "transparency").map_err(error)?,
+            "illum" => {
+                let token = args
+                    .next()
+                    .ok_or(ParseError::MissingArgument("illumination model"))
+                    .map_err(error)?;
+
+                material.illum = token
+                    .parse()
+                    .map_err(|_| error(ParseError::InvalidNumber(token.to_string())))?;
+            }
+            _ => {}
+        }
+    }
+
+    if let Some((name, material)) = current {
+        out.insert(name, material.build());
+    }
+
+    Ok(out)
+}
+
+pub fn load_mtl(path: impl AsRef<Path>) -> Result<Materials, LoadError> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path).map_err(|e| LoadError::Io(path.to_path_buf(), e))?;
+    parse_mtl(&source)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{dvec3, DVec2};
+
+    use super::*;
+    use crate::hittable::Hit;
+    use crate::material::Flags;
+
+    #[test]
+    fn parse_materials() {
+        let source = "\
+# A comment
+newmtl red
+Kd 0.8 0.1 0.1
+illum 2
+
+newmtl glass
+Ni 1.33
+d 0.5
+";
+        let materials = parse_mtl(source).unwrap();
+
+        assert_eq!(materials.len(), 2);
+        assert!(materials.contains_key("red"));
+        assert!(materials.contains_key("glass"));
+    }
+
+    // What kind of material was built shows in its flags, and its colour in
+    // the weight of a sample straight back up the normal
+    fn lobes(material: &Arc<dyn Material>) -> (Flags, DVec3) {
+        let hit = Hit::new(
+            DVec3::ZERO,
+            DVec3::Y,
+            1.0,
+            true,
+            DVec2::ZERO,
+            material.clone(),
+        );
+        let bsdf = material.bsdf(&hit).unwrap();
+        let sample = bsdf.sample(DVec3::Y, DVec3::splat(0.5)).unwrap();
+        (bsdf.flags(), sample.weight)
+    }
+
+    #[test]
+    fn illumination_models() {
+        let source = "\
+newmtl matte
+Kd 0.8 0.2 0.2
+illum 2
+
+newmtl shiny
+Kd 0.1 0.1 0.1
+Ks 0.9 0.9 0.9
+Ns 1000
+
+newmtl mirror
+Kd 0.8 0.2 0.2
+illum 3
+
+newmtl glass
+Ni 1.5
+illum 7
+";
+        let materials = parse_mtl(source).unwrap();
+
+        // Lambertian
+        let (flags, colour) = lobes(&materials["matte"]);
+        assert_eq!(flags, Flags::DIFFUSE | Flags::REFLECTION);
+        assert_eq!(colour, dvec3(0.8, 0.2, 0.2));
+
+        // Metal from a brighter Ks, fuzzed by Ns
+        let (flags, colour) = lobes(&materials["shiny"]);
+        assert_eq!(flags, Flags::GLOSSY | Flags::REFLECTION);
+        assert_eq!(colour, DVec3::splat(0.9));
+
+        // Metal without Ks or Ns is a perfect mirror coloured by Kd
+        let (flags, colour) = lobes(&materials["mirror"]);
+        assert_eq!(flags, Flags::SPECULAR | Flags::REFLECTION);
+        assert_eq!(colour, dvec3(0.8, 0.2, 0.2));
+
+        // Dielectric
+        let (flags, _) = lobes(&materials["glass"]);
+        assert_eq!(
+            flags,
+            Flags::SPECULAR | Flags::REFLECTION | Flags::TRANSMISSION
+        );
+    }
+
+    #[test]
+    fn invalid_number_has_line() {
+        let source = "newmtl red\nKd 0.8 x 0.1\n";
+
+        match parse_mtl(source) {
+            Err(LoadError::Mtl { line, error }) => {
+                assert_eq!(line, 2);
+                assert_eq!(error, ParseError::InvalidNumber("x".to_string()));
+            }
+            _ => panic!("expected an error"),
+        }
+    }
+}
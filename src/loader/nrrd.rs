@@ -0,0 +1,317 @@
+use std::fs;
+use std::path::Path;
+
+use super::error::{LoadError, ParseError};
+use crate::volume::DensityGrid;
+
+#[derive(Clone, Copy)]
+enum SampleType {
+    U8,
+    U16,
+    F32,
+    F64,
+}
+
+impl SampleType {
+    fn parse(name: &str) -> Result<Self, ParseError> {
+        match name {
+            "uchar" | "unsigned char" | "uint8" | "uint8_t" => Ok(SampleType::U8),
+            "ushort" | "unsigned short" | "uint16" | "uint16_t" => Ok(SampleType::U16),
+            "float" => Ok(SampleType::F32),
+            "double" => Ok(SampleType::F64),
+            _ => Err(ParseError::Unsupported(format!("type '{name}'"))),
+        }
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            SampleType::U8 => 1,
+            SampleType::U16 => 2,
+            SampleType::F32 => 4,
+            SampleType::F64 => 8,
+        }
+    }
+
+    fn decode(&self, bytes: &[u8], big_endian: bool) -> f32 {
+        let mut buffer = [0u8; 8];
+        let buffer = &mut buffer[..bytes.len()];
+        buffer.copy_from_slice(bytes);
+
+        if big_endian {
+            buffer.reverse();
+        }
+
+        match self {
+            SampleType::U8 => buffer[0] as f32,
+            SampleType::U16 => u16::from_le_bytes([buffer[0], buffer[1]]) as f32,
+            SampleType::F32 => f32::from_le_bytes(buffer.try_into().unwrap()),
+            SampleType::F64 => f64::from_le_bytes(buffer.try_into().unwrap()) as f32,
+        }
+    }
+}
+
+fn decode_raw(
+    bytes: &[u8],
+    sample_type: SampleType,
+    big_endian: bool,
+    count: usize,
+) -> Result<Vec<f32>, ParseError> {
+    let found = bytes.len() / sample_type.size();
+
+    if found < count {
+        return Err(ParseError::TooFewValues {
+            expected: count,
+            found,
+        });
+    }
+
+    Ok(bytes
+        .chunks_exact(sample_type.size())
+        .take(count)
+        .map(|x| sample_type.decode(x, big_endian))
+        .collect())
+}
+
+// Number of samples in a grid, checked so bad sizes can't overflow
+fn sample_count(sizes: [usize; 3]) -> Result<usize, ParseError> {
+    if sizes.contains(&0) {
+        return Err(ParseError::Unsupported("empty grid".into()));
+    }
+
+    sizes
+        .iter()
+        .try_fold(1usize, |count, &size| count.checked_mul(size))
+        .ok_or(ParseError::Unsupported(format!("grid size {sizes:?}")))
+}
+
+// Headerless little endian 32 bit floats, x varies fastest
+pub fn parse_raw(bytes: &[u8], sizes: [usize; 3]) -> Result<DensityGrid, LoadError> {
+    let count = sample_count(sizes).map_err(LoadError::Raw)?;
+    let data = decode_raw(bytes, SampleType::F32, false, count).map_err(LoadError::Raw)?;
+    Ok(DensityGrid::new(sizes, data))
+}
+
+pub fn load_raw(path: impl AsRef<Path>, sizes: [usize; 3]) -> Result<DensityGrid, LoadError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|e| LoadError::Io(path.to_path_buf(), e))?;
+    parse_raw(&bytes, sizes)
+}
+
+// The subset of NRRD simulation exports need: 3 dimensional uchar, ushort,
+// float or double samples with raw or ascii encoding in the same file
+pub fn parse_nrrd(bytes: &[u8]) -> Result<DensityGrid, LoadError> {
+    let mut sample_type = None;
+    let mut sizes = None;
+    let mut encoding = None;
+    let mut big_endian = false;
+
+    let mut offset = 0;
+    let mut line = 0;
+
+    loop {
+        line += 1;
+        let error = |error| LoadError::Nrrd { line, error };
+
+        let end = bytes[offset..]
+            .iter()
+            .position(|&x| x == b'\n')
+            .map(|x| offset + x)
+            .ok_or(error(ParseError::MissingArgument("data")))?;
+
+        let text = String::from_utf8_lossy(&bytes[offset..end]);
+        let text = text.trim_end_matches('\r');
+        offset = end + 1;
+
+        if line == 1 {
+            if !text.starts_with("NRRD") {
+                return Err(error(ParseError::Unsupported(
+                    "file, missing NRRD magic".into(),
+                )));
+            }
+            continue;
+        }
+
+        // Blank line separates the header from the data
+        if text.is_empty() {
+            break;
+        }
+
+        // Comments and key/value pairs
+        if text.starts_with('#') || text.contains(":=") {
+            continue;
+        }
+
+        let Some((field, value)) = text.split_once(": ") else {
+            return Err(error(ParseError::Unsupported(format!(
+                "header line '{text}'"
+            ))));
+        };
+
+        let value = value.trim();
+
+        match field {
+            "type" => sample_type = Some(SampleType::parse(value).map_err(error)?),
+            "dimension" if value != "3" => {
+                return Err(error(ParseError::Unsupported(format!("dimension {value}"))));
+            }
+            "sizes" => {
+                let parsed = value
+                    .split_whitespace()
+                    .map(|x| {
+                        x.parse::<usize>()
+                            .map_err(|_| ParseError::InvalidNumber(x.to_string()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(error)?;
+
+                sizes = Some(
+                    <[usize; 3]>::try_from(parsed)
+                        .map_err(|_| error(ParseError::MissingArgument("3 sizes")))?,
+                );
+            }
+            "encoding" => match value {
+                "raw" => encoding = Some(true),
+                "ascii" | "text" | "txt" => encoding = Some(false),
+                _ => {
+                    return Err(error(ParseError::Unsupported(format!(
+                        "encoding '{value}'"
+                    ))))
+                }
+            },
+            "endian" => big_endian = value == "big",
+            "data file" | "datafile" => {
+                return Err(error(ParseError::Unsupported("detached data".into())));
+            }
+            // Spacings, space directions etc. don't matter, the grid is
+            // placed by GridVolume's bounds
+            _ => {}
+        }
+    }
+
+    let error = |error| LoadError::Nrrd { line, error };
+    let sample_type = sample_type.ok_or(error(ParseError::MissingArgument("type")))?;
+    let sizes = sizes.ok_or(error(ParseError::MissingArgument("sizes")))?;
+    let raw = encoding.ok_or(error(ParseError::MissingArgument("encoding")))?;
+
+    let count = sample_count(sizes).map_err(error)?;
+    let data = &bytes[offset..];
+
+    let data = if raw {
+        decode_raw(data, sample_type, big_endian, count).map_err(error)?
+    } else {
+        let values = String::from_utf8_lossy(data)
+            .split_whitespace()
+            .take(count)
+            .map(|x| {
+                x.parse::<f32>()
+                    .map_err(|_| ParseError::InvalidNumber(x.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(error)?;
+
+        if values.len() < count {
+            return Err(error(ParseError::TooFewValues {
+                expected: count,
+                found: values.len(),
+            }));
+        }
+
+        values
+    };
+
+    Ok(DensityGrid::new(sizes, data))
+}
+
+pub fn load_nrrd(path: impl AsRef<Path>) -> Result<DensityGrid, LoadError> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|e| LoadError::Io(path.to_path_buf(), e))?;
+    parse_nrrd(&bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+
+    #[test]
+    fn parse_ascii_and_raw() {
+        let ascii = b"NRRD0004\n# comment\ntype: float\ndimension: 3\nsizes: 2 1 1\nencoding: ascii\n\n0.5 1.5\n";
+        let grid = parse_nrrd(ascii).unwrap();
+
+        assert_eq!(grid.sizes(), [2, 1, 1]);
+        assert_eq!(grid.sample(dvec3(0.5, 0.5, 0.5)), 1.0);
+
+        let mut raw =
+            b"NRRD0004\ntype: ushort\ndimension: 3\nsizes: 1 2 1\nencoding: raw\nendian: big\n\n"
+                .to_vec();
+        raw.extend([0, 2, 1, 0]);
+        let grid = parse_nrrd(&raw).unwrap();
+
+        assert_eq!(grid.max(), 256.0);
+        assert_eq!(grid.sample(dvec3(0.5, 0.0, 0.5)), 2.0);
+    }
+
+    #[test]
+    fn headerless_floats() {
+        let bytes: Vec<u8> = [1.0f32, 2.0, 3.0, 4.0]
+            .iter()
+            .flat_map(|x| x.to_le_bytes())
+            .collect();
+
+        assert_eq!(parse_raw(&bytes, [2, 2, 1]).unwrap().max(), 4.0);
+        match parse_raw(&bytes, [2, 2, 2]) {
+            Err(LoadError::Raw(error)) => assert_eq!(
+                error,
+                ParseError::TooFewValues {
+                    expected: 8,
+                    found: 4
+                }
+            ),
+            _ => panic!("expected an error"),
+        }
+
+        for sizes in [[2, 0, 2], [usize::MAX, 2, 1]] {
+            assert!(matches!(
+                parse_raw(&bytes, sizes),
+                Err(LoadError::Raw(ParseError::Unsupported(_)))
+            ));
+        }
+    }
+
+    #[test]
+    fn header_errors() {
+        let cases: [(&[u8], usize, ParseError); 4] = [
+            (
+                b"P6\n",
+                1,
+                ParseError::Unsupported("file, missing NRRD magic".into()),
+            ),
+            (
+                b"NRRD0004\ntype: float\ndimension: 2\n\n",
+                3,
+                ParseError::Unsupported("dimension 2".into()),
+            ),
+            (
+                b"NRRD0004\ntype: float\ndimension: 3\nencoding: raw\n\n",
+                5,
+                ParseError::MissingArgument("sizes"),
+            ),
+            (
+                b"NRRD0004\ntype: float\ndimension: 3\nsizes: 4294967296 4294967296 1\nencoding: raw\n\n",
+                6,
+                ParseError::Unsupported("grid size [4294967296, 4294967296, 1]".into()),
+            ),
+        ];
+
+        for (source, expected_line, expected_error) in cases {
+            match parse_nrrd(source) {
+                Err(LoadError::Nrrd { line, error }) => {
+                    assert_eq!(line, expected_line);
+                    assert_eq!(error, expected_error);
+                }
+                _ => panic!("expected an error"),
+            }
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use glam::{dvec2, DVec2, DVec3};
+
+use super::error::{LoadError, ParseError};
+use super::mtl::{load_mtl, Materials};
+use super::parse::{number, tokenize, vector};
+use crate::hittable::{Face, HittableList, TriangleMesh};
+use crate::material::{Lambertian, Material};
+
+// Absolute indices of position, uv and normal
+type Corner = (usize, Option<usize>, Option<usize>);
+
+struct Group {
+    material: Arc<dyn Material>,
+    triangles: Vec<[Corner; 3]>,
+}
+
+// Converts 1-based (or negative relative) indices to 0-based
+fn resolve(token: &str, len: usize) -> Result<usize, ParseError> {
+    let index: i64 = token
+        .parse()
+        .map_err(|_| ParseError::InvalidIndex(token.to_string()))?;
+
+    let resolved = if index > 0 {
+        index - 1
+    } else {
+        len as i64 + index
+    };
+
+    if index == 0 || resolved < 0 || resolved >= len as i64 {
+        return Err(ParseError::IndexOutOfRange(index));
+    }
+
+    Ok(resolved as usize)
+}
+
+fn optional(token: Option<&str>, len: usize) -> Result<Option<usize>, ParseError> {
+    match token {
+        None | Some("") => Ok(None),
+        Some(token) => resolve(token, len).map(Some),
+    }
+}
+
+// Supports v, v/vt, v//vn and v/vt/vn
+fn corner(token: &str, lens: (usize, usize, usize)) -> Result<Corner, ParseError> {
+    let mut parts = token.split('/');
+
+    let position = resolve(parts.next().unwrap_or(""), lens.0)?;
+    let uv = optional(parts.next(), lens.1)?;
+    let normal = optional(parts.next(), lens.2)?;
+
+    if parts.next().is_some() {
+        return Err(ParseError::InvalidIndex(token.to_string()));
+    }
+
+    Ok((position, uv, normal))
+}
+
+// Gives each group its own compact buffers so unused vertices aren't copied
+fn build_mesh(group: Group, positions: &[DVec3], uvs: &[DVec2], normals: &[DVec3]) -> TriangleMesh {
+    fn remap<T: Copy>(
+        index: usize,
+        source: &[T],
+        map: &mut HashMap<usize, usize>,
+        out: &mut Vec<T>,
+    ) -> usize {
+        *map.entry(index).or_insert_with(|| {
+            out.push(source[index]);
+            out.len() - 1
+        })
+    }
+
+    let mut maps = (HashMap::new(), HashMap::new(), HashMap::new());
+    let mut buffers = (vec![], vec![], vec![]);
+    let mut faces = vec![];
+
+    for triangle in group.triangles {
+        let face_positions =
+            triangle.map(|(p, _, _)| remap(p, positions, &mut maps.0, &mut buffers.0));
+
+        // Attributes are only used if every corner has them
+        let face_uvs = triangle.iter().all(|(_, uv, _)| uv.is_some()).then(|| {
+            triangle.map(|(_, uv, _)| remap(uv.unwrap(), uvs, &mut maps.1, &mut buffers.1))
+        });
+
+        let face_normals = triangle.iter().all(|(_, _, n)| n.is_some()).then(|| {
+            triangle.map(|(_, _, n)| remap(n.unwrap(), normals, &mut maps.2, &mut buffers.2))
+        });
+
+        faces.push(Face {
+            positions: face_positions,
+            normals: face_normals,
+            uvs: face_uvs,
+        });
+    }
+
+    TriangleMesh::new(buffers.0, buffers.2, buffers.1, faces, group.material)
+}
+
+// Material libraries are looked up relative to directory
+pub fn parse_obj(source: &str, directory: &Path) -> Result<HittableList, LoadError> {
+    let mut positions = vec![];
+    let mut uvs = vec![];
+    let mut normals = vec![];
+
+    let mut materials = Materials::new();
+
+    // Keyed by group and material name, faces are split so each mesh has one material
+    let mut groups: Vec<Group> = vec![];
+    let mut lookup: HashMap<(String, String), usize> = HashMap::new();
+
+    let mut group_name = String::new();
+    let mut material_name = String::new();
+    // Used for faces before any usemtl
+    let mut material: Arc<dyn Material> = Arc::new(Lambertian::new(DVec3::splat(0.8)));
+
+    for (i, line) in source.lines().enumerate() {
+        let error = |error| LoadError::Obj { line: i + 1, error };
+
+        let Some((keyword, mut args)) = tokenize(line) else {
+            continue;
+        };
+
+        match keyword {
+            "v" => positions.push(vector(&mut args, "vertex position").map_err(error)?),
+            "vn" => normals.push(vector(&mut args, "vertex normal").map_err(error)?),
+            "vt" => {
+                let u = number(args.next(), "texture coordinate").map_err(error)?;
+
+                // v is optional in the spec
+                let v = args
+                    .next()
+                    .map_or(Ok(0.0), |x| number(Some(x), "texture coordinate"));
+                uvs.push(dvec2(u, v.map_err(error)?));
+            }
+            "f" => {
+                let lens = (positions.len(), uvs.len(), normals.len());
+                let corners = args
+                    .map(|token| corner(token, lens))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(error)?;
+
+                if corners.len() < 3 {
+                    return Err(error(ParseError::TooFewVertices(corners.len())));
+                }
+
+                let key = (group_name.clone(), material_name.clone());
+                let index = *lookup.entry(key).or_insert_with(|| {
+                    groups.push(Group {
+                        material: material.clone(),
+                        triangles: vec![],
+                    });
+                    groups.len() - 1
+                });
+
+                // Fan triangulation, fine for the convex polygons OBJ files contain
+                for j in 1..corners.len() - 1 {
+                    groups[index]
+                        .triangles
+                        .push([corners[0], corners[j], corners[j + 1]]);
+                }
+            }
+            "g" | "o" => group_name = args.collect::<Vec<_>>().join(" "),
+            "usemtl" => {
+                let name = args
+                    .next()
+                    .ok_or(ParseError::MissingArgument("material name"))
+                    .map_err(error)?;
+
+                material = materials
+                    .get(name)
+                    .ok_or(ParseError::UnknownMaterial(name.to_string()))
+                    .map_err(error)?
+                    .clone();
+                material_name = name.to_string();
+            }
+            "mtllib" => {
+                for name in args {
+                    materials.extend(load_mtl(directory.join(name))?);
+                }
+            }
+            // Smoothing groups, lines, points etc. are ignored
+            _ => {}
+        }
+    }
+
+    let mut out = HittableList::new();
+
+    for group in groups {
+        out.add(build_mesh(group, &positions, &uvs, &normals));
+    }
+
+    Ok(out)
+}
+
+pub fn load_obj(path: impl AsRef<Path>) -> Result<HittableList, LoadError> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path).map_err(|e| LoadError::Io(path.to_path_buf(), e))?;
+    parse_obj(&source, path.parent().unwrap_or(Path::new(".")))
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+    use crate::hittable::Hittable;
+    use crate::ray::Ray;
+    use crate::vec::approx_eq;
+
+    fn parse(source: &str) -> Result<HittableList, LoadError> {
+        parse_obj(source, Path::new("."))
+    }
+
+    #[test]
+    fn parse_quad_with_negative_indices() {
+        let source = "\
+v 0 0 0
+v 1 0 0
+v 1 1 0
+v 0 1 0
+vt 0 0
+vt 1 0
+vt 1 1
+vt 0 1
+vn 0 0 1
+f -4/-4/-1 -3/-3/-1 -2/-2/-1 -1/-1/-1
+";
+        let world = parse(source).unwrap();
+        assert_eq!(world.objects.len(), 1);
+
+        let ray = Ray::new(dvec3(0.25, 0.75, 1.0), dvec3(0.0, 0.0, -1.0));
+        let hit = world.hit(&ray, 1e-9, f64::MAX).unwrap();
+
+        assert!(approx_eq(hit.point, dvec3(0.25, 0.75, 0.0)));
+        assert!(approx_eq(hit.normal, dvec3(0.0, 0.0, 1.0)));
+        assert!((hit.uv - dvec2(0.25, 0.75)).length() < 1e-9);
+    }
+
+    #[test]
+    fn groups_become_meshes() {
+        let source = "\
+v 0 0 0
+v 1 0 0
+v 0 1 0
+g first
+f 1 2 3
+g second
+f 1//1 3//1 2//1
+";
+        // Normal index refers to a normal that doesn't exist
+        match parse(source) {
+            Err(LoadError::Obj { line, error }) => {
+                assert_eq!(line, 7);
+                assert_eq!(error, ParseError::IndexOutOfRange(1));
+            }
+            _ => panic!("expected an error"),
+        }
+
+        let world = parse(&source.replace("1//1 3//1 2//1", "1 3 2")).unwrap();
+        assert_eq!(world.objects.len(), 2);
+    }
+
+    #[test]
+    fn errors_have_line_numbers() {
+        let cases = [
+            ("v 0 0\n", 1, ParseError::MissingArgument("vertex position")),
+            (
+                "v 0 0 0\nv 1 0 0\nf 1 2\n",
+                3,
+                ParseError::TooFewVertices(2),
+            ),
+            (
+                "v 0 0 0\nv 1 0 0\nf 1 2 a\n",
+                3,
+                ParseError::InvalidIndex("a".to_string()),
+            ),
+            (
+                "usemtl missing\n",
+                1,
+                ParseError::UnknownMaterial("missing".to_string()),
+            ),
+            (
+                "vn 0 0 one\n",
+                1,
+                ParseError::InvalidNumber("one".to_string()),
+            ),
+        ];
+
+        for (source, expected_line, expected_error) in cases {
+            match parse(source) {
+                Err(LoadError::Obj { line, error }) => {
+                    assert_eq!(line, expected_line);
+                    assert_eq!(error, expected_error);
+                }
+                _ => panic!("expected an error for {source:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use glam::{dvec3, DVec3};
+
+use super::error::ParseError;
+
+pub fn number(token: Option<&str>, name: &'static str) -> Result<f64, ParseError> {
+    let token = token.ok_or(ParseError::MissingArgument(name))?;
+
+    token
+        .parse()
+        .map_err(|_| ParseError::InvalidNumber(token.to_string()))
+}
+
+pub fn vector<'a>(
+    tokens: &mut impl Iterator<Item = &'a str>,
+    name: &'static str,
+) -> Result<DVec3, ParseError> {
+    Ok(dvec3(
+        number(tokens.next(), name)?,
+        number(tokens.next(), name)?,
+        number(tokens.next(), name)?,
+    ))
+}
+
+// Strips comments and splits into keyword and arguments
+pub fn tokenize(line: &str) -> Option<(&str, std::str::SplitWhitespace<'_>)> {
+    let line = line.split('#').next().unwrap_or("");
+    let mut tokens = line.split_whitespace();
+    tokens.next().map(|keyword| (keyword, tokens))
+}
@@ -3,20 +3,18 @@ use std::time::Instant;
 
 use glam::{dvec3, DVec3};
 use raytracer::camera::{Camera, CameraParams};
-use raytracer::hittable::{HittableList, Sphere};
+use raytracer::hittable::{Bvh, HittableList, Plane, Sphere};
 use raytracer::material::{Dielectric, Lambertian, Material, Metal};
+use raytracer::output::save_linear;
 use raytracer::random::random_colour;
+use raytracer::scene::Scene;
 
 fn main() {
     let mut world = HittableList::new();
 
     let ground_material = Arc::new(Lambertian::new(dvec3(0.5, 0.5, 0.5)));
 
-    world.add(Sphere::new(
-        dvec3(0.0, -1000.0, 0.0),
-        1000.0,
-        ground_material,
-    ));
+    world.add(Plane::new(DVec3::ZERO, DVec3::Y, ground_material));
 
     for x in -11..11 {
         for z in -11..11 {
@@ -64,6 +62,8 @@ fn main() {
         Arc::new(Metal::new(dvec3(0.7, 0.6, 0.5), 0.0)),
     ));
 
+    let scene = Scene::new(Bvh::from(world));
+
     let mut params = CameraParams::default();
 
     params.width = 1920;
@@ -80,10 +80,11 @@ fn main() {
     let cam = Camera::from(&params);
 
     let time = Instant::now();
-    let img = cam.render(&world);
+    let img = cam.render(&scene);
     let duration = time.elapsed();
 
     println!("Took {duration:.2?}");
 
-    img.save("out.png").unwrap();
+    save_linear(&img, "out.exr").unwrap();
+    cam.develop(&img).save("out.png").unwrap();
 }
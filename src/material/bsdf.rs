@@ -0,0 +1,108 @@
+use std::ops::BitOr;
+
+use glam::DVec3;
+
+use super::frame::Frame;
+
+// What kind of scattering a lobe or a whole BSDF does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Flags(u8);
+
+impl Flags {
+    pub const REFLECTION: Flags = Flags(1);
+    pub const TRANSMISSION: Flags = Flags(2);
+    pub const DIFFUSE: Flags = Flags(4);
+    pub const GLOSSY: Flags = Flags(8);
+    // Delta distributions such as mirrors and smooth glass, eval and pdf are
+    // zero so only sample can find them
+    pub const SPECULAR: Flags = Flags(16);
+
+    pub fn contains(self, other: Flags) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn is_specular(self) -> bool {
+        self.contains(Flags::SPECULAR)
+    }
+
+    // Has a lobe that light sampling can hit
+    pub fn is_non_specular(self) -> bool {
+        self.0 & (Flags::DIFFUSE.0 | Flags::GLOSSY.0) != 0
+    }
+}
+
+impl BitOr for Flags {
+    type Output = Flags;
+
+    fn bitor(self, other: Flags) -> Flags {
+        Flags(self.0 | other.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct BsdfSample {
+    pub wi: DVec3,
+    // eval(wo, wi) / pdf, what the path's throughput is multiplied by
+    pub weight: DVec3,
+    // Solid angle density of choosing wi from every lobe, or the discrete
+    // probability of a specular lobe
+    pub pdf: f64,
+    // The lobe that was sampled
+    pub flags: Flags,
+}
+
+// Scattering at a point in its local shading frame, where the normal is z
+// and faces wo. Phase functions for volumes use the same interface, with the
+// normal pointing back along the ray
+pub trait Bsdf: Send + Sync {
+    // BSDF times |cos| of wi for scattering light arriving from wi towards
+    // wo. Phase functions have no cosine term
+    fn eval(&self, wo: DVec3, wi: DVec3) -> DVec3;
+
+    // Picks wi for wo from uniform random numbers in [0, 1)^3, x is used to
+    // choose between lobes and y, z for the direction
+    fn sample(&self, wo: DVec3, u: DVec3) -> Option<BsdfSample>;
+
+    // Density of sample choosing wi, zero for specular lobes
+    fn pdf(&self, wo: DVec3, wi: DVec3) -> f64;
+
+    // Every lobe the BSDF has
+    fn flags(&self) -> Flags;
+}
+
+// A BSDF placed on a surface, converting world space directions to and from
+// its shading frame
+pub struct SurfaceBsdf {
+    frame: Frame,
+    bsdf: Box<dyn Bsdf>,
+}
+
+impl SurfaceBsdf {
+    pub fn new<B: Bsdf + 'static>(frame: Frame, bsdf: B) -> Self {
+        SurfaceBsdf {
+            frame,
+            bsdf: Box::new(bsdf),
+        }
+    }
+
+    pub fn eval(&self, wo: DVec3, wi: DVec3) -> DVec3 {
+        self.bsdf
+            .eval(self.frame.to_local(wo), self.frame.to_local(wi.normalize()))
+    }
+
+    // wi comes back in world space
+    pub fn sample(&self, wo: DVec3, u: DVec3) -> Option<BsdfSample> {
+        let mut sample = self.bsdf.sample(self.frame.to_local(wo), u)?;
+        sample.wi = self.frame.to_world(sample.wi);
+        Some(sample)
+    }
+
+    pub fn pdf(&self, wo: DVec3, wi: DVec3) -> f64 {
+        self.bsdf
+            .pdf(self.frame.to_local(wo), self.frame.to_local(wi.normalize()))
+    }
+
+    pub fn flags(&self) -> Flags {
+        self.bsdf.flags()
+    }
+}
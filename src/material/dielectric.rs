@@ -1,8 +1,9 @@
-use glam::DVec3;
+use glam::{dvec3, DVec3};
 
+use super::bsdf::{Bsdf, BsdfSample, Flags, SurfaceBsdf};
+use super::frame::Frame;
 use super::traits::Material;
 use crate::hittable::Hit;
-use crate::ray::Ray;
 
 fn reflectance(cos_theta: f64, eta: f64) -> f64 {
     let tmp = (1.0 - eta) / (1.0 + eta);
@@ -10,6 +11,58 @@ fn reflectance(cos_theta: f64, eta: f64) -> f64 {
     r_0 + (1.0 - r_0) * (1.0 - cos_theta).powi(5)
 }
 
+// Smooth glass, reflects or refracts with the Fresnel probability
+struct DielectricBsdf {
+    // Ratio of the refractive indexes, outside over inside for the side the
+    // ray is on
+    eta: f64,
+}
+
+impl Bsdf for DielectricBsdf {
+    // Both lobes are delta distributions
+    fn eval(&self, _wo: DVec3, _wi: DVec3) -> DVec3 {
+        DVec3::ZERO
+    }
+
+    fn sample(&self, wo: DVec3, u: DVec3) -> Option<BsdfSample> {
+        // Set upper limit to 1.0 due to rounding errors
+        let cos_theta = wo.z.min(1.0);
+        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+
+        let reflect = if self.eta * sin_theta > 1.0 {
+            1.0
+        } else {
+            reflectance(cos_theta, self.eta)
+        };
+
+        let sample = if u.x < reflect {
+            BsdfSample {
+                wi: dvec3(-wo.x, -wo.y, wo.z),
+                weight: DVec3::ONE,
+                pdf: reflect,
+                flags: Flags::SPECULAR | Flags::REFLECTION,
+            }
+        } else {
+            BsdfSample {
+                wi: (-wo).refract(DVec3::Z, self.eta),
+                weight: DVec3::ONE,
+                pdf: 1.0 - reflect,
+                flags: Flags::SPECULAR | Flags::TRANSMISSION,
+            }
+        };
+
+        Some(sample)
+    }
+
+    fn pdf(&self, _wo: DVec3, _wi: DVec3) -> f64 {
+        0.0
+    }
+
+    fn flags(&self) -> Flags {
+        Flags::SPECULAR | Flags::REFLECTION | Flags::TRANSMISSION
+    }
+}
+
 pub struct Dielectric {
     refractive_index: f64,
 }
@@ -21,25 +74,44 @@ impl Dielectric {
 }
 
 impl Material for Dielectric {
-    fn scatter(&self, ray: &Ray, hit: &Hit) -> Option<(DVec3, Option<Ray>)> {
+    fn bsdf(&self, hit: &Hit) -> Option<SurfaceBsdf> {
         // Assume surrounding medium is 1.0 (air)
-        // eta is the ratio of the refractive indexes
         let eta = if hit.front_face {
             1.0 / self.refractive_index
         } else {
             self.refractive_index
         };
 
-        // Set upper limit to 1.0 due to rounding errors
-        let cos_theta = (-ray.direction.dot(hit.normal)).min(1.0);
-        let sin_theta = (1.0 - cos_theta * cos_theta).sqrt();
+        Some(SurfaceBsdf::new(
+            Frame::new(hit.normal),
+            DielectricBsdf { eta },
+        ))
+    }
+}
 
-        let direction = if eta * sin_theta > 1.0 || reflectance(cos_theta, eta) > fastrand::f64() {
-            ray.direction.reflect(hit.normal)
-        } else {
-            ray.direction.refract(hit.normal, eta)
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn total_internal_reflection() {
+        let bsdf = DielectricBsdf { eta: 1.5 };
+        let wo = dvec3(0.9, 0.0, 0.1).normalize();
+
+        // Even the last u that would refract reflects
+        let sample = bsdf.sample(wo, dvec3(0.999, 0.5, 0.5)).unwrap();
+        assert!(sample.flags.contains(Flags::REFLECTION));
+        assert_eq!(sample.pdf, 1.0);
+    }
+
+    #[test]
+    fn refracts_towards_normal() {
+        let bsdf = DielectricBsdf { eta: 1.0 / 1.5 };
+        let wo = dvec3(1.0, 0.0, 1.0).normalize();
 
-        Some((DVec3::ONE, Some(Ray::new(hit.point, direction))))
+        let sample = bsdf.sample(wo, dvec3(0.999, 0.5, 0.5)).unwrap();
+        assert!(sample.flags.contains(Flags::TRANSMISSION));
+        assert!(sample.wi.z < 0.0);
+        assert!((sample.wi.x.abs() - (0.5f64.sqrt() / 1.5)).abs() < 1e-9);
     }
 }
@@ -0,0 +1,28 @@
+use glam::DVec3;
+
+use super::bsdf::SurfaceBsdf;
+use super::traits::Material;
+use crate::hittable::Hit;
+use crate::ray::Ray;
+
+pub struct DiffuseLight {
+    emit: DVec3,
+}
+
+impl DiffuseLight {
+    pub fn new(emit: DVec3) -> Self {
+        DiffuseLight { emit }
+    }
+}
+
+impl Material for DiffuseLight {
+    // Lights absorb everything
+    fn bsdf(&self, _hit: &Hit) -> Option<SurfaceBsdf> {
+        None
+    }
+
+    // Emits equally from both sides
+    fn emitted(&self, _ray: &Ray, _hit: &Hit) -> DVec3 {
+        self.emit
+    }
+}
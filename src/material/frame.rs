@@ -0,0 +1,69 @@
+use glam::DVec3;
+
+// Orthonormal basis for working in local shading space, where the normal is
+// z and the tangent is x so anisotropic lobes line up with it
+#[derive(Debug, Clone, Copy)]
+pub struct Frame {
+    pub tangent: DVec3,
+    pub bitangent: DVec3,
+    pub normal: DVec3,
+}
+
+impl Frame {
+    // Any tangent, for materials that look the same in every direction
+    pub fn new(normal: DVec3) -> Self {
+        let (tangent, bitangent) = normal.any_orthonormal_pair();
+        Frame {
+            tangent,
+            bitangent,
+            normal,
+        }
+    }
+
+    // Tangent is projected onto the surface, falls back to any tangent when
+    // it points along the normal
+    pub fn from_tangent(normal: DVec3, tangent: DVec3) -> Self {
+        let tangent = tangent - normal * normal.dot(tangent);
+
+        match tangent.try_normalize() {
+            Some(tangent) => Frame {
+                tangent,
+                bitangent: normal.cross(tangent),
+                normal,
+            },
+            None => Frame::new(normal),
+        }
+    }
+
+    pub fn to_local(&self, v: DVec3) -> DVec3 {
+        DVec3::new(
+            v.dot(self.tangent),
+            v.dot(self.bitangent),
+            v.dot(self.normal),
+        )
+    }
+
+    pub fn to_world(&self, v: DVec3) -> DVec3 {
+        v.x * self.tangent + v.y * self.bitangent + v.z * self.normal
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+    use crate::vec::approx_eq;
+
+    #[test]
+    fn round_trip() {
+        let normal = dvec3(1.0, 2.0, -0.5).normalize();
+        let frame = Frame::from_tangent(normal, DVec3::X);
+        let v = dvec3(0.3, -0.7, 0.2);
+
+        assert!(approx_eq(frame.to_world(frame.to_local(v)), v));
+        assert!(approx_eq(frame.to_local(normal), DVec3::Z));
+        assert!(frame.tangent.dot(normal).abs() < 1e-12);
+        assert!(frame.to_local(DVec3::X).x > 0.0);
+    }
+}
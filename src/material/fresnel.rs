@@ -0,0 +1,68 @@
+use glam::DVec3;
+
+// Reflectance at the angle with cosine cos_theta from the reflectance f0
+// looking straight on
+pub fn schlick(f0: DVec3, cos_theta: f64) -> DVec3 {
+    f0 + (1.0 - f0) * (1.0 - cos_theta.clamp(0.0, 1.0)).powi(5)
+}
+
+// Exact unpolarised reflectance of a dielectric, eta is the ratio of the
+// refractive index on the far side over the near side. 1 for total internal
+// reflection
+pub fn dielectric(cos_theta: f64, eta: f64) -> f64 {
+    let cos_i = cos_theta.clamp(0.0, 1.0);
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+
+    if sin2_t >= 1.0 {
+        return 1.0;
+    }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let parallel = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    let perpendicular = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+
+    0.5 * (parallel * parallel + perpendicular * perpendicular)
+}
+
+// Exact unpolarised reflectance of a metal from its complex refractive index
+// eta + i k per channel, measured values are in tables such as
+// refractiveindex.info
+pub fn conductor(cos_theta: f64, eta: DVec3, k: DVec3) -> DVec3 {
+    let cos_i = cos_theta.clamp(0.0, 1.0);
+    let cos2 = cos_i * cos_i;
+    let sin2 = 1.0 - cos2;
+
+    let t0 = eta * eta - k * k - sin2;
+    let a2_plus_b2 = (t0 * t0 + 4.0 * eta * eta * k * k).powf(0.5);
+    let t1 = a2_plus_b2 + cos2;
+    let a = (0.5 * (a2_plus_b2 + t0)).max(DVec3::ZERO).powf(0.5);
+    let t2 = 2.0 * cos_i * a;
+    let perpendicular = (t1 - t2) / (t1 + t2);
+
+    let t3 = cos2 * a2_plus_b2 + sin2 * sin2;
+    let t4 = t2 * sin2;
+    let parallel = perpendicular * (t3 - t4) / (t3 + t4);
+
+    0.5 * (parallel + perpendicular)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn straight_on() {
+        // ((n - 1) / (n + 1))^2 for glass
+        assert!((dielectric(1.0, 1.5) - 0.04).abs() < 1e-12);
+        assert_eq!(dielectric(0.1, 1.0 / 1.5), 1.0);
+
+        // A conductor without absorption is a dielectric
+        let eta = DVec3::splat(1.5);
+        for cos_theta in [1.0, 0.7, 0.2] {
+            let r = conductor(cos_theta, eta, DVec3::ZERO);
+            assert!((r.x - dielectric(cos_theta, 1.5)).abs() < 1e-9);
+        }
+
+        assert_eq!(schlick(DVec3::splat(0.5), 0.0), DVec3::ONE);
+    }
+}
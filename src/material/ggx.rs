@@ -0,0 +1,161 @@
+use std::f64::consts::PI;
+
+use glam::{dvec3, DVec2, DVec3};
+
+// GGX / Trowbridge-Reitz microfacet distribution in the local shading frame
+// where the normal is z. alpha is roughness squared, separately along the
+// tangent (x) and bitangent (y) for anisotropic surfaces
+#[derive(Debug, Clone, Copy)]
+pub struct Ggx {
+    pub alpha_x: f64,
+    pub alpha_y: f64,
+}
+
+impl Ggx {
+    // Below this sampling and evaluation break down numerically, it's close
+    // enough to a mirror anyway
+    const MIN_ALPHA: f64 = 1e-4;
+
+    // Perceptual roughness and anisotropy in [0, 1] the way DCC tools (and
+    // Disney's principled BRDF) take them
+    pub fn new(roughness: f64, anisotropy: f64) -> Self {
+        let alpha = roughness.clamp(0.0, 1.0).powi(2);
+        let aspect = (1.0 - 0.9 * anisotropy.clamp(0.0, 1.0)).sqrt();
+
+        Ggx {
+            alpha_x: (alpha / aspect).max(Self::MIN_ALPHA),
+            alpha_y: (alpha * aspect).max(Self::MIN_ALPHA),
+        }
+    }
+
+    // Density of microfacet normals m, projected area per steradian
+    pub fn d(&self, m: DVec3) -> f64 {
+        if m.z <= 0.0 {
+            return 0.0;
+        }
+
+        let tmp = dvec3(m.x / self.alpha_x, m.y / self.alpha_y, m.z).length_squared();
+        1.0 / (PI * self.alpha_x * self.alpha_y * tmp * tmp)
+    }
+
+    // Smith's auxiliary function for the shadowing of direction w
+    fn lambda(&self, w: DVec3) -> f64 {
+        if w.z == 0.0 {
+            return f64::INFINITY;
+        }
+
+        let tan2 = ((self.alpha_x * w.x).powi(2) + (self.alpha_y * w.y).powi(2)) / (w.z * w.z);
+        0.5 * ((1.0 + tan2).sqrt() - 1.0)
+    }
+
+    // Fraction of microfacets visible from w
+    pub fn g1(&self, w: DVec3) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    // Height correlated masking-shadowing, facets hidden from one direction
+    // tend to be hidden from the other so this is less than g1 * g1
+    pub fn g2(&self, wo: DVec3, wi: DVec3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    // Pdf of sample_visible choosing normal m seen from wo
+    pub fn pdf_visible(&self, wo: DVec3, m: DVec3) -> f64 {
+        if wo.z <= 0.0 {
+            return 0.0;
+        }
+
+        self.g1(wo) * wo.dot(m).max(0.0) * self.d(m) / wo.z
+    }
+
+    // Samples only the normals wo can see, so nothing is wasted on facets
+    // facing away (Heitz 2018, "Sampling the GGX Distribution of Visible
+    // Normals"). u is uniform in [0, 1)^2, wo must be above the surface
+    pub fn sample_visible(&self, wo: DVec3, u: DVec2) -> DVec3 {
+        // Stretch to the hemisphere configuration
+        let vh = dvec3(self.alpha_x * wo.x, self.alpha_y * wo.y, wo.z).normalize();
+
+        let length_squared = vh.x * vh.x + vh.y * vh.y;
+        let t1 = if length_squared > 0.0 {
+            dvec3(-vh.y, vh.x, 0.0) / length_squared.sqrt()
+        } else {
+            DVec3::X
+        };
+        let t2 = vh.cross(t1);
+
+        // Point on a disk, squashed where the hemisphere is hidden
+        let r = u.x.sqrt();
+        let phi = 2.0 * PI * u.y;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).sqrt() + s * r * phi.sin();
+
+        let nh = p1 * t1 + p2 * t2 + (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt() * vh;
+
+        // Back to the ellipsoid configuration
+        dvec3(self.alpha_x * nh.x, self.alpha_y * nh.y, nh.z.max(1e-6)).normalize()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec2;
+
+    use super::*;
+
+    // Uniform directions over the hemisphere on a grid of cells
+    fn hemisphere(n: usize) -> impl Iterator<Item = DVec3> {
+        (0..n * n).map(move |i| {
+            let (a, b) = ((i % n) as f64, (i / n) as f64);
+            let z = (a + 0.5) / n as f64;
+            let phi = 2.0 * PI * (b + 0.5) / n as f64;
+            let r = (1.0 - z * z).sqrt();
+            dvec3(r * phi.cos(), r * phi.sin(), z)
+        })
+    }
+
+    #[test]
+    fn projected_area_is_one() {
+        // Integral of D(m) cos(m) over the hemisphere
+        for ggx in [Ggx::new(0.3, 0.0), Ggx::new(0.7, 0.8)] {
+            let n = 1000;
+            let sum: f64 = hemisphere(n).map(|m| ggx.d(m) * m.z).sum();
+            let integral = sum * 2.0 * PI / (n * n) as f64;
+
+            assert!((integral - 1.0).abs() < 1e-2, "{integral}");
+        }
+    }
+
+    #[test]
+    fn visible_normals_match_pdf() {
+        let ggx = Ggx::new(0.5, 0.6);
+        let wo = dvec3(0.6, -0.3, 0.5).normalize();
+
+        // Fraction of samples in a cone around the normal against the
+        // integral of the pdf over it
+        let cone = 0.9;
+        let n = 200;
+        let inside = (0..n * n)
+            .map(|i| {
+                let u = dvec2(
+                    ((i % n) as f64 + 0.5) / n as f64,
+                    ((i / n) as f64 + 0.5) / n as f64,
+                );
+                ggx.sample_visible(wo, u)
+            })
+            .filter(|m| m.z > cone)
+            .count() as f64
+            / (n * n) as f64;
+
+        let grid = 1000;
+        let integral = hemisphere(grid)
+            .filter(|m| m.z > cone)
+            .map(|m| ggx.pdf_visible(wo, m))
+            .sum::<f64>()
+            * 2.0
+            * PI
+            / (grid * grid) as f64;
+
+        assert!((inside - integral).abs() < 1e-2, "{inside} {integral}");
+    }
+}
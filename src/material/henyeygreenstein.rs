@@ -0,0 +1,137 @@
+use std::f64::consts::PI;
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use super::bsdf::{Bsdf, BsdfSample, Flags, SurfaceBsdf};
+use super::frame::Frame;
+use super::traits::Material;
+use crate::hittable::Hit;
+use crate::texture::{SolidColour, Texture};
+
+// Henyey-Greenstein phase function, cos_theta is between the incoming ray
+// direction and the scattered direction
+fn phase(cos_theta: f64, g: f64) -> f64 {
+    let denominator = 1.0 + g * g - 2.0 * g * cos_theta;
+    (1.0 - g * g) / (4.0 * PI * denominator * denominator.sqrt())
+}
+
+// Sampled exactly so the weight is just the albedo. The phase only depends
+// on the angle to the ray, which is -wo, so any frame works
+struct HenyeyGreensteinBsdf {
+    albedo: DVec3,
+    g: f64,
+}
+
+impl Bsdf for HenyeyGreensteinBsdf {
+    fn eval(&self, wo: DVec3, wi: DVec3) -> DVec3 {
+        self.albedo * self.pdf(wo, wi)
+    }
+
+    fn sample(&self, wo: DVec3, u: DVec3) -> Option<BsdfSample> {
+        let g = self.g;
+
+        let cos_theta = if g.abs() < 1e-3 {
+            1.0 - 2.0 * u.y
+        } else {
+            let tmp = (1.0 - g * g) / (1.0 - g + 2.0 * g * u.y);
+            ((1.0 + g * g - tmp * tmp) / (2.0 * g)).clamp(-1.0, 1.0)
+        };
+
+        let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+        let phi = 2.0 * PI * u.z;
+
+        let w = -wo;
+        let (a, b) = w.any_orthonormal_pair();
+        let wi = sin_theta * (phi.cos() * a + phi.sin() * b) + cos_theta * w;
+
+        Some(BsdfSample {
+            wi,
+            weight: self.albedo,
+            pdf: phase(cos_theta, g),
+            flags: Flags::GLOSSY | Flags::REFLECTION | Flags::TRANSMISSION,
+        })
+    }
+
+    fn pdf(&self, wo: DVec3, wi: DVec3) -> f64 {
+        phase(-wo.dot(wi), self.g)
+    }
+
+    fn flags(&self) -> Flags {
+        Flags::GLOSSY | Flags::REFLECTION | Flags::TRANSMISSION
+    }
+}
+
+// Volume phase function, g > 0 scatters forwards (fog, clouds) and g < 0
+// backwards, 0 is isotropic
+pub struct HenyeyGreenstein {
+    albedo: Arc<dyn Texture>,
+    g: f64,
+}
+
+impl HenyeyGreenstein {
+    pub fn new(albedo: DVec3, g: f64) -> Self {
+        HenyeyGreenstein::textured(Arc::new(SolidColour::new(albedo)), g)
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>, g: f64) -> Self {
+        // Everything goes one way at +-1
+        let g = g.clamp(-0.999, 0.999);
+        HenyeyGreenstein { albedo, g }
+    }
+}
+
+impl Material for HenyeyGreenstein {
+    fn bsdf(&self, hit: &Hit) -> Option<SurfaceBsdf> {
+        let albedo = self.albedo.value(hit);
+        Some(SurfaceBsdf::new(
+            Frame::new(hit.normal),
+            HenyeyGreensteinBsdf { albedo, g: self.g },
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::random_cube;
+
+    #[test]
+    fn phase_integrates_to_one() {
+        for g in [-0.7, 0.0, 0.3, 0.9] {
+            // Integrate over cos_theta, phi contributes 2 pi
+            let n = 100_000;
+            let sum: f64 = (0..n)
+                .map(|i| phase(-1.0 + 2.0 * (i as f64 + 0.5) / n as f64, g))
+                .sum();
+
+            assert!(
+                (sum * 2.0 / n as f64 * 2.0 * PI - 1.0).abs() < 1e-3,
+                "g = {g}"
+            );
+        }
+    }
+
+    #[test]
+    fn samples_forwards() {
+        let material = HenyeyGreenstein::new(DVec3::ONE, 0.8);
+        // Volume hits face back along the ray, here x
+        let hit = Hit::new(
+            DVec3::ZERO,
+            -DVec3::X,
+            1.0,
+            true,
+            glam::DVec2::ZERO,
+            Arc::new(HenyeyGreenstein::new(DVec3::ONE, 0.8)),
+        );
+        let bsdf = material.bsdf(&hit).unwrap();
+
+        let mean: f64 = (0..1000)
+            .map(|_| bsdf.sample(-DVec3::X, random_cube()).unwrap().wi.x)
+            .sum::<f64>()
+            / 1000.0;
+
+        // Mean cosine of Henyey-Greenstein is g
+        assert!((mean - 0.8).abs() < 0.05);
+    }
+}
@@ -0,0 +1,71 @@
+use std::f64::consts::{PI, TAU};
+use std::sync::Arc;
+
+use glam::{dvec3, DVec2, DVec3, Vec3Swizzles};
+
+use super::bsdf::{Bsdf, BsdfSample, Flags, SurfaceBsdf};
+use super::frame::Frame;
+use super::traits::Material;
+use crate::hittable::Hit;
+use crate::texture::{SolidColour, Texture};
+
+// Picking z uniformly gives equal area bands (Archimedes)
+pub(crate) fn uniform_sphere(u: DVec2) -> DVec3 {
+    let z = 1.0 - 2.0 * u.x;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = TAU * u.y;
+    dvec3(r * phi.cos(), r * phi.sin(), z)
+}
+
+struct IsotropicBsdf {
+    albedo: DVec3,
+}
+
+impl Bsdf for IsotropicBsdf {
+    // Phase functions have no cosine term
+    fn eval(&self, wo: DVec3, wi: DVec3) -> DVec3 {
+        self.albedo * self.pdf(wo, wi)
+    }
+
+    fn sample(&self, _wo: DVec3, u: DVec3) -> Option<BsdfSample> {
+        Some(BsdfSample {
+            wi: uniform_sphere(u.yz()),
+            weight: self.albedo,
+            pdf: 1.0 / (4.0 * PI),
+            flags: Flags::DIFFUSE | Flags::REFLECTION | Flags::TRANSMISSION,
+        })
+    }
+
+    fn pdf(&self, _wo: DVec3, _wi: DVec3) -> f64 {
+        1.0 / (4.0 * PI)
+    }
+
+    fn flags(&self) -> Flags {
+        Flags::DIFFUSE | Flags::REFLECTION | Flags::TRANSMISSION
+    }
+}
+
+// Phase function scattering equally in every direction, for volumes
+pub struct Isotropic {
+    albedo: Arc<dyn Texture>,
+}
+
+impl Isotropic {
+    pub fn new(albedo: DVec3) -> Self {
+        Isotropic::textured(Arc::new(SolidColour::new(albedo)))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>) -> Self {
+        Isotropic { albedo }
+    }
+}
+
+impl Material for Isotropic {
+    fn bsdf(&self, hit: &Hit) -> Option<SurfaceBsdf> {
+        let albedo = self.albedo.value(hit);
+        Some(SurfaceBsdf::new(
+            Frame::new(hit.normal),
+            IsotropicBsdf { albedo },
+        ))
+    }
+}
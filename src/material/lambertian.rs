@@ -1,31 +1,78 @@
-use glam::DVec3;
+use std::f64::consts::{FRAC_1_PI, TAU};
+use std::sync::Arc;
 
+use glam::{dvec3, DVec2, DVec3, Vec3Swizzles};
+
+use super::bsdf::{Bsdf, BsdfSample, Flags, SurfaceBsdf};
+use super::frame::Frame;
 use super::traits::Material;
 use crate::hittable::Hit;
-use crate::random::random_unit_vector;
-use crate::ray::Ray;
-use crate::vec::approx_zero;
+use crate::texture::{SolidColour, Texture};
 
-pub struct Lambertian {
+// Cosine weighted direction around z
+pub(crate) fn cosine_hemisphere(u: DVec2) -> DVec3 {
+    let r = u.x.sqrt();
+    let phi = TAU * u.y;
+    dvec3(r * phi.cos(), r * phi.sin(), (1.0 - u.x).max(0.0).sqrt())
+}
+
+// Ideal diffuse reflection of a colour
+pub struct LambertianBsdf {
     albedo: DVec3,
 }
 
+impl LambertianBsdf {
+    pub fn new(albedo: DVec3) -> Self {
+        LambertianBsdf { albedo }
+    }
+}
+
+impl Bsdf for LambertianBsdf {
+    fn eval(&self, wo: DVec3, wi: DVec3) -> DVec3 {
+        self.albedo * self.pdf(wo, wi)
+    }
+
+    // Cosine weighted so the weight is just the albedo
+    fn sample(&self, _wo: DVec3, u: DVec3) -> Option<BsdfSample> {
+        let wi = cosine_hemisphere(u.yz());
+
+        Some(BsdfSample {
+            wi,
+            weight: self.albedo,
+            pdf: wi.z * FRAC_1_PI,
+            flags: Flags::DIFFUSE | Flags::REFLECTION,
+        })
+    }
+
+    fn pdf(&self, _wo: DVec3, wi: DVec3) -> f64 {
+        wi.z.max(0.0) * FRAC_1_PI
+    }
+
+    fn flags(&self) -> Flags {
+        Flags::DIFFUSE | Flags::REFLECTION
+    }
+}
+
+pub struct Lambertian {
+    albedo: Arc<dyn Texture>,
+}
+
 impl Lambertian {
     pub fn new(albedo: DVec3) -> Self {
+        Lambertian::textured(Arc::new(SolidColour::new(albedo)))
+    }
+
+    pub fn textured(albedo: Arc<dyn Texture>) -> Self {
         Lambertian { albedo }
     }
 }
 
 impl Material for Lambertian {
-    // Doesn't use input ray
-    fn scatter(&self, _ray: &Ray, hit: &Hit) -> Option<(DVec3, Option<Ray>)> {
-        let mut direction = hit.normal + random_unit_vector();
-
-        // Avoid problems with normalising later
-        if approx_zero(direction) {
-            direction = hit.normal;
-        }
-
-        Some((self.albedo, Some(Ray::new(hit.point, direction))))
+    fn bsdf(&self, hit: &Hit) -> Option<SurfaceBsdf> {
+        let albedo = self.albedo.value(hit);
+        Some(SurfaceBsdf::new(
+            Frame::new(hit.normal),
+            LambertianBsdf::new(albedo),
+        ))
     }
 }
@@ -0,0 +1,244 @@
+use std::sync::Arc;
+
+use glam::{DVec3, Vec3Swizzles};
+
+use super::bsdf::{Bsdf, BsdfSample, Flags, SurfaceBsdf};
+use super::frame::Frame;
+use super::fresnel::{conductor, schlick};
+use super::ggx::Ggx;
+use super::traits::Material;
+use crate::hittable::Hit;
+use crate::texture::{SolidColour, Texture};
+
+enum Reflectance {
+    // Colour looking straight on, the metalness workflow's base colour
+    Schlick(Arc<dyn Texture>),
+    // Complex refractive index per channel
+    Conductor { eta: DVec3, k: DVec3 },
+}
+
+// Rough metal from a GGX distribution of mirror-like microfacets. Unlike
+// Metal's fuzz it conserves energy and importance samples the visible normals
+// so it also works with light sampling
+pub struct Microfacet {
+    reflectance: Reflectance,
+    ggx: Ggx,
+    roughness: f64,
+    anisotropy: f64,
+    // World direction highlights stretch along when anisotropic
+    tangent: DVec3,
+}
+
+impl Microfacet {
+    // Roughness goes from a mirror at 0 to very dull at 1
+    pub fn new(colour: DVec3, roughness: f64) -> Self {
+        Microfacet::textured(Arc::new(SolidColour::new(colour)), roughness)
+    }
+
+    pub fn textured(colour: Arc<dyn Texture>, roughness: f64) -> Self {
+        Microfacet::with_reflectance(Reflectance::Schlick(colour), roughness)
+    }
+
+    // Measured metals, e.g. gold is roughly eta (0.18, 0.42, 1.37) and
+    // k (3.42, 2.35, 1.77) for red, green and blue
+    pub fn conductor(eta: DVec3, k: DVec3, roughness: f64) -> Self {
+        Microfacet::with_reflectance(Reflectance::Conductor { eta, k }, roughness)
+    }
+
+    fn with_reflectance(reflectance: Reflectance, roughness: f64) -> Self {
+        Microfacet {
+            reflectance,
+            ggx: Ggx::new(roughness, 0.0),
+            roughness,
+            anisotropy: 0.0,
+            tangent: DVec3::X,
+        }
+    }
+
+    // 0 is isotropic, towards 1 highlights stretch along the tangent like
+    // brushed metal
+    pub fn with_anisotropy(mut self, anisotropy: f64) -> Self {
+        self.anisotropy = anisotropy;
+        self.ggx = Ggx::new(self.roughness, anisotropy);
+        self
+    }
+
+    // Projected onto the surface at each hit, defaults to x
+    pub fn with_tangent(mut self, tangent: DVec3) -> Self {
+        self.tangent = tangent;
+        self
+    }
+
+    fn frame(&self, hit: &Hit) -> Frame {
+        if self.anisotropy == 0.0 {
+            Frame::new(hit.normal)
+        } else {
+            Frame::from_tangent(hit.normal, self.tangent)
+        }
+    }
+
+    fn fresnel(&self, hit: &Hit) -> Fresnel {
+        match &self.reflectance {
+            Reflectance::Schlick(colour) => Fresnel::Schlick(colour.value(hit)),
+            Reflectance::Conductor { eta, k } => Fresnel::Conductor { eta: *eta, k: *k },
+        }
+    }
+}
+
+impl Material for Microfacet {
+    fn bsdf(&self, hit: &Hit) -> Option<SurfaceBsdf> {
+        Some(SurfaceBsdf::new(
+            self.frame(hit),
+            MicrofacetBsdf {
+                fresnel: self.fresnel(hit),
+                ggx: self.ggx,
+            },
+        ))
+    }
+}
+
+// Reflectance with textures looked up at the hit
+enum Fresnel {
+    Schlick(DVec3),
+    Conductor { eta: DVec3, k: DVec3 },
+}
+
+impl Fresnel {
+    fn value(&self, cos_theta: f64) -> DVec3 {
+        match self {
+            Fresnel::Schlick(f0) => schlick(*f0, cos_theta),
+            Fresnel::Conductor { eta, k } => conductor(cos_theta, *eta, *k),
+        }
+    }
+}
+
+struct MicrofacetBsdf {
+    fresnel: Fresnel,
+    ggx: Ggx,
+}
+
+impl Bsdf for MicrofacetBsdf {
+    fn eval(&self, wo: DVec3, wi: DVec3) -> DVec3 {
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return DVec3::ZERO;
+        }
+
+        let m = (wo + wi).normalize();
+
+        // D G2 F / (4 cos_o cos_i) times cos_i
+        let d = self.ggx.d(m);
+        self.fresnel.value(wo.dot(m)) * d * self.ggx.g2(wo, wi) / (4.0 * wo.z)
+    }
+
+    // Reflecting off a visible normal gives the weight F G2 / G1
+    fn sample(&self, wo: DVec3, u: DVec3) -> Option<BsdfSample> {
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        let m = self.ggx.sample_visible(wo, u.yz());
+        let wi = (-wo).reflect(m);
+
+        // Reflected under the surface, shadowed by the other facets
+        if wi.z <= 0.0 {
+            return None;
+        }
+
+        Some(BsdfSample {
+            wi,
+            weight: self.fresnel.value(wo.dot(m)) * self.ggx.g2(wo, wi) / self.ggx.g1(wo),
+            pdf: self.pdf(wo, wi),
+            flags: Flags::GLOSSY | Flags::REFLECTION,
+        })
+    }
+
+    fn pdf(&self, wo: DVec3, wi: DVec3) -> f64 {
+        if wo.z <= 0.0 || wi.z <= 0.0 {
+            return 0.0;
+        }
+
+        // The reflection doubles angles, so the pdf is divided by 4 (wo . m)
+        let m = (wo + wi).normalize();
+        self.ggx.pdf_visible(wo, m) / (4.0 * wo.dot(m))
+    }
+
+    fn flags(&self) -> Flags {
+        Flags::GLOSSY | Flags::REFLECTION
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::TAU;
+
+    use glam::{dvec3, DVec2};
+
+    use super::*;
+    use crate::random::random_cube;
+
+    fn bsdf(material: Microfacet) -> SurfaceBsdf {
+        let material: Arc<dyn Material> = Arc::new(material);
+        let hit = Hit::new(
+            DVec3::ZERO,
+            DVec3::Y,
+            1.0,
+            true,
+            DVec2::ZERO,
+            material.clone(),
+        );
+        material.bsdf(&hit).unwrap()
+    }
+
+    #[test]
+    fn sample_matches_eval() {
+        let bsdf = bsdf(
+            Microfacet::conductor(dvec3(0.18, 0.42, 1.37), dvec3(3.42, 2.35, 1.77), 0.4)
+                .with_anisotropy(0.7),
+        );
+        let wo = dvec3(-1.0, 1.0, 0.3).normalize();
+
+        for _ in 0..100 {
+            let Some(sample) = bsdf.sample(wo, random_cube()) else {
+                continue;
+            };
+
+            let f = bsdf.eval(wo, sample.wi);
+            let pdf = bsdf.pdf(wo, sample.wi);
+
+            assert!((sample.pdf - pdf).abs() < 1e-9);
+            assert!((sample.weight - f / pdf).abs().max_element() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn white_furnace() {
+        // A perfect reflector only loses energy to shadowing between facets,
+        // sampling and evaluating must agree on how much
+        let bsdf = bsdf(Microfacet::new(DVec3::ONE, 0.5));
+        let wo = dvec3(-1.0, 1.0, 0.0).normalize();
+
+        let n = 20_000;
+        let sampled = (0..n)
+            .filter_map(|_| bsdf.sample(wo, random_cube()))
+            .map(|sample| sample.weight.x)
+            .sum::<f64>()
+            / n as f64;
+
+        // Midpoint rule over the hemisphere around the normal (y)
+        let grid = 400;
+        let integral = (0..grid * grid)
+            .map(|i| {
+                let y = ((i % grid) as f64 + 0.5) / grid as f64;
+                let phi = TAU * ((i / grid) as f64 + 0.5) / grid as f64;
+                let r = (1.0 - y * y).sqrt();
+                let direction = dvec3(r * phi.cos(), y, r * phi.sin());
+                bsdf.eval(wo, direction).x
+            })
+            .sum::<f64>()
+            * TAU
+            / (grid * grid) as f64;
+
+        assert!(sampled < 1.0 && sampled > 0.8, "{sampled}");
+        assert!((sampled - integral).abs() < 0.01, "{sampled} {integral}");
+    }
+}
@@ -1,11 +1,30 @@
+pub mod bsdf;
 pub mod dielectric;
+pub mod diffuselight;
+pub mod frame;
+pub mod fresnel;
+pub mod ggx;
+pub mod henyeygreenstein;
+pub mod isotropic;
 pub mod lambertian;
 pub mod metal;
+pub mod microfacet;
+pub mod principled;
 pub mod rainbow;
 pub mod traits;
+pub mod trapcolour;
 
+pub use bsdf::{Bsdf, BsdfSample, Flags, SurfaceBsdf};
 pub use dielectric::Dielectric;
-pub use lambertian::Lambertian;
+pub use diffuselight::DiffuseLight;
+pub use frame::Frame;
+pub use ggx::Ggx;
+pub use henyeygreenstein::HenyeyGreenstein;
+pub use isotropic::Isotropic;
+pub use lambertian::{Lambertian, LambertianBsdf};
 pub use metal::Metal;
+pub use microfacet::Microfacet;
+pub use principled::{Parameter, Principled};
 pub use rainbow::Rainbow;
 pub use traits::Material;
+pub use trapcolour::{ColourGradient, TrapChannel, TrapColour};
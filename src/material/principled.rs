@@ -0,0 +1,601 @@
+use std::f64::consts::{FRAC_1_PI, PI, TAU};
+use std::sync::Arc;
+
+use glam::{dvec3, DVec2, DVec3, Vec3Swizzles};
+
+use super::bsdf::{Bsdf, BsdfSample, Flags, SurfaceBsdf};
+use super::frame::Frame;
+use super::fresnel::{dielectric, schlick};
+use super::ggx::Ggx;
+use super::lambertian::cosine_hemisphere;
+use super::traits::Material;
+use crate::hittable::Hit;
+use crate::texture::{SolidColour, Texture};
+use crate::vec::luminance;
+
+// A constant or a texture, scalar parameters read the texture's red channel
+#[derive(Clone)]
+pub struct Parameter(Arc<dyn Texture>);
+
+impl Parameter {
+    fn colour(&self, hit: &Hit) -> DVec3 {
+        self.0.value(hit)
+    }
+
+    fn scalar(&self, hit: &Hit) -> f64 {
+        self.colour(hit).x
+    }
+}
+
+impl From<f64> for Parameter {
+    fn from(value: f64) -> Self {
+        Parameter(Arc::new(SolidColour::new(DVec3::splat(value))))
+    }
+}
+
+impl From<DVec3> for Parameter {
+    fn from(value: DVec3) -> Self {
+        Parameter(Arc::new(SolidColour::new(value)))
+    }
+}
+
+impl From<Arc<dyn Texture>> for Parameter {
+    fn from(texture: Arc<dyn Texture>) -> Self {
+        Parameter(texture)
+    }
+}
+
+// Parameters looked up at one hit
+struct Inputs {
+    base_colour: DVec3,
+    metallic: f64,
+    roughness: f64,
+    specular: f64,
+    specular_tint: f64,
+    sheen: f64,
+    sheen_tint: f64,
+    clearcoat: f64,
+    transmission: f64,
+    // Inside over outside for the side the ray is on
+    eta: f64,
+
+    specular_ggx: Ggx,
+    clearcoat_alpha: f64,
+}
+
+impl Inputs {
+    fn diffuse_weight(&self) -> f64 {
+        (1.0 - self.metallic) * (1.0 - self.transmission)
+    }
+
+    fn transmission_weight(&self) -> f64 {
+        (1.0 - self.metallic) * self.transmission
+    }
+
+    // Hue of the base colour without its brightness
+    fn tint(&self) -> DVec3 {
+        let luminance = luminance(self.base_colour);
+
+        if luminance > 0.0 {
+            self.base_colour / luminance
+        } else {
+            DVec3::ONE
+        }
+    }
+
+    // Reflectance straight on, dielectrics around 4% and metals their colour
+    fn specular_colour(&self) -> DVec3 {
+        let dielectric = 0.08 * self.specular * DVec3::ONE.lerp(self.tint(), self.specular_tint);
+        dielectric.lerp(self.base_colour, self.metallic)
+    }
+}
+
+// Chances of sampling each lobe
+struct LobeWeights {
+    diffuse: f64,
+    specular: f64,
+    clearcoat: f64,
+    transmission: f64,
+}
+
+// Uber material with the lobes of Disney's principled BSDF (Burley 2012 and
+// 2015) so assets from DCC tools can be used as authored:
+//
+// - Diffuse with retro-reflection at grazing angles, plus sheen for cloth
+// - Anisotropic GGX specular, tinted by base colour as it becomes metallic
+// - A second fixed roughness clear coat on top, e.g. car paint
+// - Rough glass transmission
+//
+// Subsurface scattering is left out
+pub struct Principled {
+    base_colour: Parameter,
+    metallic: Parameter,
+    roughness: Parameter,
+    anisotropy: Parameter,
+    specular: Parameter,
+    specular_tint: Parameter,
+    sheen: Parameter,
+    sheen_tint: Parameter,
+    clearcoat: Parameter,
+    clearcoat_gloss: Parameter,
+    transmission: Parameter,
+    ior: Parameter,
+    // World direction anisotropic highlights stretch along
+    tangent: DVec3,
+}
+
+impl Principled {
+    // Everything else starts as a rough plastic
+    pub fn new(base_colour: impl Into<Parameter>) -> Self {
+        Principled {
+            base_colour: base_colour.into(),
+            metallic: 0.0.into(),
+            roughness: 0.5.into(),
+            anisotropy: 0.0.into(),
+            specular: 0.5.into(),
+            specular_tint: 0.0.into(),
+            sheen: 0.0.into(),
+            sheen_tint: 0.5.into(),
+            clearcoat: 0.0.into(),
+            clearcoat_gloss: 1.0.into(),
+            transmission: 0.0.into(),
+            ior: 1.5.into(),
+            tangent: DVec3::X,
+        }
+    }
+
+    pub fn with_metallic(mut self, metallic: impl Into<Parameter>) -> Self {
+        self.metallic = metallic.into();
+        self
+    }
+
+    pub fn with_roughness(mut self, roughness: impl Into<Parameter>) -> Self {
+        self.roughness = roughness.into();
+        self
+    }
+
+    // Stretches highlights along the tangent, see Microfacet
+    pub fn with_anisotropy(mut self, anisotropy: impl Into<Parameter>) -> Self {
+        self.anisotropy = anisotropy.into();
+        self
+    }
+
+    pub fn with_tangent(mut self, tangent: DVec3) -> Self {
+        self.tangent = tangent;
+        self
+    }
+
+    // 0.5 is 4% reflectance straight on, which suits most dielectrics
+    pub fn with_specular(mut self, specular: impl Into<Parameter>) -> Self {
+        self.specular = specular.into();
+        self
+    }
+
+    // Colours dielectric highlights towards the base colour's hue
+    pub fn with_specular_tint(mut self, specular_tint: impl Into<Parameter>) -> Self {
+        self.specular_tint = specular_tint.into();
+        self
+    }
+
+    pub fn with_sheen(mut self, sheen: impl Into<Parameter>) -> Self {
+        self.sheen = sheen.into();
+        self
+    }
+
+    pub fn with_sheen_tint(mut self, sheen_tint: impl Into<Parameter>) -> Self {
+        self.sheen_tint = sheen_tint.into();
+        self
+    }
+
+    pub fn with_clearcoat(mut self, clearcoat: impl Into<Parameter>) -> Self {
+        self.clearcoat = clearcoat.into();
+        self
+    }
+
+    // 0 is satin and 1 is glossy
+    pub fn with_clearcoat_gloss(mut self, clearcoat_gloss: impl Into<Parameter>) -> Self {
+        self.clearcoat_gloss = clearcoat_gloss.into();
+        self
+    }
+
+    // Objects must be closed to transmit, like Dielectric
+    pub fn with_transmission(mut self, transmission: impl Into<Parameter>) -> Self {
+        self.transmission = transmission.into();
+        self
+    }
+
+    pub fn with_ior(mut self, ior: impl Into<Parameter>) -> Self {
+        self.ior = ior.into();
+        self
+    }
+
+    fn inputs(&self, hit: &Hit) -> Inputs {
+        let unit = |parameter: &Parameter| parameter.scalar(hit).clamp(0.0, 1.0);
+
+        let roughness = unit(&self.roughness);
+        let ior = self.ior.scalar(hit).max(1.0);
+
+        Inputs {
+            base_colour: self.base_colour.colour(hit).max(DVec3::ZERO),
+            metallic: unit(&self.metallic),
+            roughness,
+            specular: self.specular.scalar(hit).max(0.0),
+            specular_tint: unit(&self.specular_tint),
+            sheen: self.sheen.scalar(hit).max(0.0),
+            sheen_tint: unit(&self.sheen_tint),
+            clearcoat: self.clearcoat.scalar(hit).max(0.0),
+            transmission: unit(&self.transmission),
+            eta: if hit.front_face { ior } else { 1.0 / ior },
+
+            specular_ggx: Ggx::new(roughness, unit(&self.anisotropy)),
+            clearcoat_alpha: 0.1 + (0.001 - 0.1) * unit(&self.clearcoat_gloss),
+        }
+    }
+
+    fn lobe_weights(inputs: &Inputs, cos_o: f64) -> LobeWeights {
+        let specular = (1.0 - inputs.transmission_weight())
+            * luminance(schlick(inputs.specular_colour(), cos_o));
+
+        LobeWeights {
+            diffuse: inputs.diffuse_weight(),
+            specular,
+            clearcoat: 0.25 * inputs.clearcoat * schlick(DVec3::splat(0.04), cos_o).x,
+            transmission: inputs.transmission_weight(),
+        }
+    }
+
+    // BSDF times cosine and the combined pdf of every lobe sampling wi
+    fn evaluate_local(inputs: &Inputs, wo: DVec3, wi: DVec3) -> (DVec3, f64) {
+        let weights = Self::lobe_weights(inputs, wo.z);
+        let total = weights.diffuse + weights.specular + weights.clearcoat + weights.transmission;
+
+        if total <= 0.0 || wo.z <= 0.0 || wi.z == 0.0 {
+            return (DVec3::ZERO, 0.0);
+        }
+
+        let ggx = &inputs.specular_ggx;
+        let transmission = inputs.transmission_weight();
+
+        if wi.z < 0.0 {
+            let (bsdf, pdf) = refraction(inputs, wo, wi);
+            return (transmission * bsdf, weights.transmission / total * pdf);
+        }
+
+        let m = (wo + wi).normalize();
+        let (cos_o, cos_i, cos_d) = (wo.z, wi.z, wi.dot(m));
+
+        let mut bsdf = DVec3::ZERO;
+        let mut pdf = 0.0;
+
+        // Diffuse and sheen
+        let diffuse = inputs.diffuse_weight();
+        if diffuse > 0.0 {
+            let f90 = 0.5 + 2.0 * inputs.roughness * cos_d * cos_d;
+            let retro = (1.0 + (f90 - 1.0) * (1.0 - cos_i).powi(5))
+                * (1.0 + (f90 - 1.0) * (1.0 - cos_o).powi(5));
+            let sheen = inputs.sheen
+                * DVec3::ONE.lerp(inputs.tint(), inputs.sheen_tint)
+                * (1.0 - cos_d).powi(5);
+
+            bsdf += diffuse * (inputs.base_colour * FRAC_1_PI * retro + sheen) * cos_i;
+            pdf += weights.diffuse / total * cos_i * FRAC_1_PI;
+        }
+
+        // Both reflections off the GGX facets are sampled the same way
+        let specular_pdf = ggx.pdf_visible(wo, m) / (4.0 * wo.dot(m));
+        let facets = ggx.d(m) * ggx.g2(wo, wi) / (4.0 * cos_o);
+
+        let specular = 1.0 - transmission;
+        if specular > 0.0 {
+            bsdf += specular * schlick(inputs.specular_colour(), wo.dot(m)) * facets;
+            pdf += weights.specular / total * specular_pdf;
+        }
+
+        // Reflection off glass
+        if transmission > 0.0 {
+            let fresnel = dielectric(wo.dot(m), inputs.eta);
+            bsdf += DVec3::splat(transmission * fresnel * facets);
+            pdf += weights.transmission / total * fresnel * specular_pdf;
+        }
+
+        if inputs.clearcoat > 0.0 {
+            let alpha = inputs.clearcoat_alpha;
+            let d = gtr1(m.z, alpha);
+            let g = smith_g1(cos_o, 0.25) * smith_g1(cos_i, 0.25);
+            let fresnel = schlick(DVec3::splat(0.04), wo.dot(m)).x;
+
+            bsdf += DVec3::splat(0.25 * inputs.clearcoat * fresnel * d * g / (4.0 * cos_o));
+            pdf += weights.clearcoat / total * d * m.z / (4.0 * wo.dot(m));
+        }
+
+        (bsdf, pdf)
+    }
+
+    // Picks a lobe by its weight with u.x and samples a direction from it
+    fn sample_local(inputs: &Inputs, wo: DVec3, u: DVec3) -> Option<(DVec3, Flags)> {
+        let weights = Self::lobe_weights(inputs, wo.z);
+        let total = weights.diffuse + weights.specular + weights.clearcoat + weights.transmission;
+
+        if total <= 0.0 {
+            return None;
+        }
+
+        // Facets seen at grazing angles can reflect below the surface
+        let reflect = |m: DVec3| {
+            let wi = (-wo).reflect(m);
+            (wi.z > 0.0).then_some((wi, Flags::GLOSSY | Flags::REFLECTION))
+        };
+        let mut choice = u.x * total;
+
+        if choice < weights.diffuse {
+            return Some((
+                cosine_hemisphere(u.yz()),
+                Flags::DIFFUSE | Flags::REFLECTION,
+            ));
+        }
+        choice -= weights.diffuse;
+
+        if choice < weights.specular {
+            let m = inputs.specular_ggx.sample_visible(wo, u.yz());
+            return reflect(m);
+        }
+        choice -= weights.specular;
+
+        if choice < weights.clearcoat {
+            let m = sample_gtr1(inputs.clearcoat_alpha, u.yz());
+            return reflect(m);
+        }
+        choice -= weights.clearcoat;
+
+        // Glass reflects or refracts off the same facet by Fresnel, reusing
+        // what is left of u.x
+        let m = inputs.specular_ggx.sample_visible(wo, u.yz());
+        let fresnel = dielectric(wo.dot(m), inputs.eta);
+
+        if choice < fresnel * weights.transmission {
+            reflect(m)
+        } else {
+            let wi = (-wo).refract(m, 1.0 / inputs.eta);
+            (wi != DVec3::ZERO).then_some((wi, Flags::GLOSSY | Flags::TRANSMISSION))
+        }
+    }
+}
+
+// Rough dielectric transmission times cosine and its pdf given the glass
+// lobe was picked (Walter et al. 2007)
+fn refraction(inputs: &Inputs, wo: DVec3, wi: DVec3) -> (DVec3, f64) {
+    let ggx = &inputs.specular_ggx;
+    let eta = inputs.eta;
+
+    // Generalised half vector, facing the outside
+    let mut m = (wo + wi * eta).normalize();
+    if m.z < 0.0 {
+        m = -m;
+    }
+
+    let (cos_om, cos_im) = (wo.dot(m), wi.dot(m));
+
+    // Facets facing away from either direction can't refract between them
+    if cos_om <= 0.0 || cos_im >= 0.0 {
+        return (DVec3::ZERO, 0.0);
+    }
+
+    let fresnel = dielectric(cos_om, eta);
+    let denominator = (cos_im + cos_om / eta).powi(2);
+
+    // Radiance is compressed into the smaller solid angle going in
+    let bsdf =
+        inputs.base_colour * (1.0 - fresnel) * ggx.d(m) * ggx.g2(wo, wi) * (cos_im * cos_om).abs()
+            / (wo.z * denominator * eta * eta);
+
+    let jacobian = cos_im.abs() / denominator;
+    let pdf = ggx.pdf_visible(wo, m) * jacobian * (1.0 - fresnel);
+
+    (bsdf, pdf)
+}
+
+// Berry's distribution for the clear coat, a longer tail than GGX
+fn gtr1(cos_m: f64, alpha: f64) -> f64 {
+    let a2 = alpha * alpha;
+    let t = 1.0 + (a2 - 1.0) * cos_m * cos_m;
+    (a2 - 1.0) / (PI * a2.ln() * t)
+}
+
+fn sample_gtr1(alpha: f64, u: DVec2) -> DVec3 {
+    let a2 = alpha * alpha;
+    let cos_theta = ((1.0 - a2.powf(1.0 - u.x)) / (1.0 - a2)).max(0.0).sqrt();
+    let sin_theta = (1.0 - cos_theta * cos_theta).max(0.0).sqrt();
+    let phi = TAU * u.y;
+    dvec3(sin_theta * phi.cos(), sin_theta * phi.sin(), cos_theta)
+}
+
+// Separable Smith GGX masking, only used by the clear coat
+fn smith_g1(cos_theta: f64, alpha: f64) -> f64 {
+    let a2 = alpha * alpha;
+    let c2 = cos_theta * cos_theta;
+    2.0 * cos_theta / (cos_theta + (a2 + c2 - a2 * c2).sqrt())
+}
+
+impl Material for Principled {
+    fn bsdf(&self, hit: &Hit) -> Option<SurfaceBsdf> {
+        Some(SurfaceBsdf::new(
+            Frame::from_tangent(hit.normal, self.tangent),
+            PrincipledBsdf {
+                inputs: self.inputs(hit),
+            },
+        ))
+    }
+}
+
+struct PrincipledBsdf {
+    inputs: Inputs,
+}
+
+impl Bsdf for PrincipledBsdf {
+    fn eval(&self, wo: DVec3, wi: DVec3) -> DVec3 {
+        Principled::evaluate_local(&self.inputs, wo, wi).0
+    }
+
+    fn sample(&self, wo: DVec3, u: DVec3) -> Option<BsdfSample> {
+        if wo.z <= 0.0 {
+            return None;
+        }
+
+        let (wi, flags) = Principled::sample_local(&self.inputs, wo, u)?;
+
+        // Weighted by every lobe that could have picked wi (one sample MIS)
+        let (bsdf, pdf) = Principled::evaluate_local(&self.inputs, wo, wi);
+
+        if pdf <= 0.0 {
+            return None;
+        }
+
+        Some(BsdfSample {
+            wi,
+            weight: bsdf / pdf,
+            pdf,
+            flags,
+        })
+    }
+
+    fn pdf(&self, wo: DVec3, wi: DVec3) -> f64 {
+        Principled::evaluate_local(&self.inputs, wo, wi).1
+    }
+
+    fn flags(&self) -> Flags {
+        let mut flags = Flags::GLOSSY | Flags::REFLECTION;
+
+        if self.inputs.diffuse_weight() > 0.0 {
+            flags = flags | Flags::DIFFUSE;
+        }
+        if self.inputs.transmission_weight() > 0.0 {
+            flags = flags | Flags::TRANSMISSION;
+        }
+
+        flags
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random::random_cube;
+
+    fn hit(material: Arc<dyn Material>) -> Hit {
+        Hit::new(DVec3::ZERO, DVec3::Y, 1.0, true, DVec2::ZERO, material)
+    }
+
+    fn bsdf(material: Principled) -> SurfaceBsdf {
+        let material: Arc<dyn Material> = Arc::new(material);
+        material.bsdf(&hit(material.clone())).unwrap()
+    }
+
+    // Mean sample weight, and the integral of eval over the sphere
+    fn albedo(material: Principled) -> (f64, f64) {
+        let bsdf = bsdf(material);
+        let wo = dvec3(-1.0, 1.0, 0.3).normalize();
+
+        let n = 50_000;
+        let sampled = (0..n)
+            .filter_map(|_| bsdf.sample(wo, random_cube()))
+            .map(|sample| sample.weight.x)
+            .sum::<f64>()
+            / n as f64;
+
+        // Midpoint rule over the sphere of directions
+        let grid = 600;
+        let integral = (0..grid * grid)
+            .map(|i| {
+                let y = -1.0 + 2.0 * ((i % grid) as f64 + 0.5) / grid as f64;
+                let phi = TAU * ((i / grid) as f64 + 0.5) / grid as f64;
+                let r = (1.0 - y * y).sqrt();
+                let direction = dvec3(r * phi.cos(), y, r * phi.sin());
+                bsdf.eval(wo, direction).x
+            })
+            .sum::<f64>()
+            * 2.0
+            * TAU
+            / (grid * grid) as f64;
+
+        (sampled, integral)
+    }
+
+    #[test]
+    fn sampling_matches_evaluate() {
+        let materials = [
+            Principled::new(DVec3::splat(0.8)),
+            Principled::new(DVec3::splat(0.9))
+                .with_metallic(1.0)
+                .with_roughness(0.6)
+                .with_anisotropy(0.5),
+            Principled::new(DVec3::splat(0.5))
+                .with_sheen(1.0)
+                .with_clearcoat(1.0)
+                .with_clearcoat_gloss(0.3),
+            Principled::new(DVec3::ONE)
+                .with_transmission(1.0)
+                .with_roughness(0.5),
+        ];
+
+        for material in materials {
+            let (sampled, integral) = albedo(material);
+            assert!((sampled - integral).abs() < 0.02, "{sampled} {integral}");
+        }
+    }
+
+    #[test]
+    fn reflections_stay_above_surface() {
+        let bsdf = bsdf(
+            Principled::new(DVec3::ONE)
+                .with_transmission(0.5)
+                .with_roughness(1.0)
+                .with_clearcoat(1.0)
+                .with_clearcoat_gloss(0.0),
+        );
+        let wo = dvec3(-1.0, 0.05, 0.0).normalize();
+
+        for _ in 0..10_000 {
+            if let Some(sample) = bsdf.sample(wo, random_cube()) {
+                let transmitted = sample.flags.contains(Flags::TRANSMISSION);
+                assert_eq!(sample.wi.y < 0.0, transmitted, "{}", sample.wi);
+            }
+        }
+    }
+
+    #[test]
+    fn smooth_glass_follows_snell() {
+        let bsdf = bsdf(
+            Principled::new(DVec3::ONE)
+                .with_transmission(1.0)
+                .with_roughness(0.0),
+        );
+        let wo = dvec3(-1.0, 1.0, 0.0).normalize();
+        let expected = (-wo).refract(DVec3::Y, 1.0 / 1.5);
+
+        let (mut reflected, mut refracted) = (0, 0);
+        for _ in 0..1000 {
+            let direction = bsdf.sample(wo, random_cube()).unwrap().wi;
+
+            if direction.y > 0.0 {
+                reflected += 1;
+            } else {
+                refracted += 1;
+                // GGX's long tail still reaches past the minimum roughness
+                assert!((direction - expected).length() < 1e-2);
+            }
+        }
+
+        // About 5% reflects at 45 degrees
+        assert!(reflected > 20 && reflected < 100);
+        assert!(refracted > 900);
+    }
+
+    #[test]
+    fn scalar_parameters_read_red() {
+        let texture: Arc<dyn Texture> = Arc::new(SolidColour::new(dvec3(0.25, 1.0, 1.0)));
+        let material = Principled::new(DVec3::ONE).with_metallic(texture);
+        let hit = hit(Arc::new(Principled::new(DVec3::ONE)));
+
+        assert_eq!(material.inputs(&hit).metallic, 0.25);
+    }
+}
@@ -1,5 +1,6 @@
 use glam::DVec3;
 
+use super::bsdf::SurfaceBsdf;
 use crate::hittable::Hit;
 use crate::material::Material;
 use crate::ray::Ray;
@@ -13,8 +14,13 @@ impl Rainbow {
     }
 }
 
+// Unlit, the colour is seen as is without lighting
 impl Material for Rainbow {
-    fn scatter(&self, _ray: &Ray, hit: &Hit) -> Option<(DVec3, Option<Ray>)> {
-        Some((hit.point.map(f64::sin).normalize(), None))
+    fn bsdf(&self, _hit: &Hit) -> Option<SurfaceBsdf> {
+        None
+    }
+
+    fn emitted(&self, _ray: &Ray, hit: &Hit) -> DVec3 {
+        hit.point.map(f64::sin).normalize()
     }
 }
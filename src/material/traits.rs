@@ -1,8 +1,14 @@
 use glam::DVec3;
 
+use super::bsdf::SurfaceBsdf;
 use crate::hittable::Hit;
 use crate::ray::Ray;
 
 pub trait Material: Send + Sync {
-    fn scatter(&self, ray: &Ray, hit: &Hit) -> Option<(DVec3, Option<Ray>)>;
+    // How light scatters at the hit, None for materials that only emit
+    fn bsdf(&self, hit: &Hit) -> Option<SurfaceBsdf>;
+
+    fn emitted(&self, _ray: &Ray, _hit: &Hit) -> DVec3 {
+        DVec3::ZERO
+    }
 }
@@ -0,0 +1,136 @@
+use glam::DVec3;
+
+use super::bsdf::SurfaceBsdf;
+use super::frame::Frame;
+use super::lambertian::LambertianBsdf;
+use super::traits::Material;
+use crate::hittable::Hit;
+use crate::sdf::OrbitTrap;
+
+// Piecewise linear colour ramp over [0, 1], clamped at the ends
+pub struct ColourGradient {
+    stops: Vec<(f64, DVec3)>,
+}
+
+impl ColourGradient {
+    // Stops are (position, colour) in any order
+    pub fn new(mut stops: Vec<(f64, DVec3)>) -> Self {
+        assert!(!stops.is_empty());
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ColourGradient { stops }
+    }
+
+    // Evenly spaced colours
+    pub fn from_colours(colours: &[DVec3]) -> Self {
+        let n = (colours.len().max(2) - 1) as f64;
+        ColourGradient::new(
+            colours
+                .iter()
+                .enumerate()
+                .map(|(i, &colour)| (i as f64 / n, colour))
+                .collect(),
+        )
+    }
+
+    pub fn value(&self, t: f64) -> DVec3 {
+        let i = self.stops.partition_point(|&(x, _)| x <= t);
+
+        if i == 0 {
+            return self.stops[0].1;
+        }
+        if i == self.stops.len() {
+            return self.stops[i - 1].1;
+        }
+
+        let (x0, a) = self.stops[i - 1];
+        let (x1, b) = self.stops[i];
+        a.lerp(b, (t - x0) / (x1 - x0))
+    }
+}
+
+// Which part of the orbit trap picks the colour
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TrapChannel {
+    Point,
+    Plane,
+    Axis,
+    Iterations,
+    Smooth,
+}
+
+impl TrapChannel {
+    fn get(&self, trap: &OrbitTrap) -> f64 {
+        match self {
+            TrapChannel::Point => trap.point,
+            TrapChannel::Plane => trap.plane,
+            TrapChannel::Axis => trap.axis,
+            TrapChannel::Iterations => trap.iterations as f64,
+            TrapChannel::Smooth => trap.smooth,
+        }
+    }
+}
+
+// Diffuse surface coloured from the orbit trap of fractal hits
+pub struct TrapColour {
+    gradient: ColourGradient,
+    channel: TrapChannel,
+    // Channel values mapped to the ends of the gradient
+    low: f64,
+    high: f64,
+}
+
+impl TrapColour {
+    pub fn new(gradient: ColourGradient, channel: TrapChannel) -> Self {
+        TrapColour {
+            gradient,
+            channel,
+            low: 0.0,
+            high: 1.0,
+        }
+    }
+
+    pub fn with_range(mut self, low: f64, high: f64) -> Self {
+        self.low = low;
+        self.high = high;
+        self
+    }
+
+    // Hits without trap data get the start of the gradient
+    fn albedo(&self, hit: &Hit) -> DVec3 {
+        let t = hit.trap.map_or(0.0, |trap| {
+            (self.channel.get(&trap) - self.low) / (self.high - self.low)
+        });
+        self.gradient.value(t)
+    }
+}
+
+impl Material for TrapColour {
+    fn bsdf(&self, hit: &Hit) -> Option<SurfaceBsdf> {
+        Some(SurfaceBsdf::new(
+            Frame::new(hit.normal),
+            LambertianBsdf::new(self.albedo(hit)),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+    use crate::vec::approx_eq;
+
+    #[test]
+    fn gradient_interpolates_and_clamps() {
+        let gradient = ColourGradient::new(vec![
+            (1.0, DVec3::ONE),
+            (0.0, DVec3::ZERO),
+            (0.5, dvec3(1.0, 0.0, 0.0)),
+        ]);
+
+        assert!(approx_eq(gradient.value(-1.0), DVec3::ZERO));
+        assert!(approx_eq(gradient.value(0.25), dvec3(0.5, 0.0, 0.0)));
+        assert!(approx_eq(gradient.value(0.75), dvec3(1.0, 0.5, 0.5)));
+        assert!(approx_eq(gradient.value(2.0), DVec3::ONE));
+    }
+}
@@ -0,0 +1,73 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use image::{ImageFormat, ImageResult, Rgb32FImage};
+
+pub fn save_exr(image: &Rgb32FImage, path: impl AsRef<Path>) -> ImageResult<()> {
+    image.save_with_format(path, ImageFormat::OpenExr)
+}
+
+// Radiance RGBE
+pub fn save_hdr(image: &Rgb32FImage, path: impl AsRef<Path>) -> ImageResult<()> {
+    image.save_with_format(path, ImageFormat::Hdr)
+}
+
+// Portable float map, written little endian with rows from bottom to top
+pub fn save_pfm(image: &Rgb32FImage, path: impl AsRef<Path>) -> ImageResult<()> {
+    let mut out = BufWriter::new(File::create(path)?);
+    write_pfm(image, &mut out)?;
+    out.flush()?;
+    Ok(())
+}
+
+fn write_pfm(image: &Rgb32FImage, out: &mut impl Write) -> std::io::Result<()> {
+    // Negative scale means little endian
+    write!(out, "PF\n{} {}\n-1.0\n", image.width(), image.height())?;
+
+    for row in image.rows().rev() {
+        for pixel in row {
+            for channel in pixel.0 {
+                out.write_all(&channel.to_le_bytes())?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// Picks the format from the file extension
+pub fn save_linear(image: &Rgb32FImage, path: impl AsRef<Path>) -> ImageResult<()> {
+    let path = path.as_ref();
+
+    match path.extension().and_then(|x| x.to_str()) {
+        Some(x) if x.eq_ignore_ascii_case("pfm") => save_pfm(image, path),
+        _ => image.save(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use image::Rgb;
+
+    use super::*;
+
+    #[test]
+    fn pfm_layout() {
+        let mut image = Rgb32FImage::new(2, 2);
+        image.put_pixel(0, 1, Rgb([1.0, 2.0, 3.0]));
+
+        let mut out = vec![];
+        write_pfm(&image, &mut out).unwrap();
+
+        let header = b"PF\n2 2\n-1.0\n";
+        assert_eq!(&out[..header.len()], header);
+        assert_eq!(out.len(), header.len() + 4 * 3 * 4);
+
+        // Bottom row comes first
+        let first = &out[header.len()..header.len() + 12];
+        assert_eq!(first[..4], 1.0f32.to_le_bytes());
+        assert_eq!(first[4..8], 2.0f32.to_le_bytes());
+        assert_eq!(first[8..], 3.0f32.to_le_bytes());
+    }
+}
@@ -20,14 +20,12 @@ pub fn random_on_disc() -> (f64, f64) {
 }
 
 pub fn random_unit_vector() -> DVec3 {
-    // Randomly distribute along sphere surface
-    let theta = fastrand::f64() * PI;
+    // Uniformly distribute along sphere surface
+    // Picking z uniformly gives equal area bands (Archimedes)
+    let z = 2.0 * fastrand::f64() - 1.0;
     let phi = fastrand::f64() * 2.0 * PI;
-    dvec3(
-        theta.sin() * phi.cos(),
-        theta.sin() * phi.sin(),
-        theta.cos(),
-    )
+    let r = (1.0 - z * z).sqrt();
+    dvec3(r * phi.cos(), r * phi.sin(), z)
 }
 
 pub fn random_on_hemisphere(normal: &DVec3) -> DVec3 {
@@ -43,3 +41,13 @@ pub fn random_on_hemisphere(normal: &DVec3) -> DVec3 {
 pub fn random_colour() -> DVec3 {
     DVec3::new(fastrand::f64(), fastrand::f64(), fastrand::f64())
 }
+
+pub fn random_exponential(rate: f64) -> f64 {
+    // Distance to the next event when events happen at rate per unit length
+    -(1.0 - fastrand::f64()).ln() / rate
+}
+
+pub fn random_cube() -> DVec3 {
+    // Random between [0, 1) on each axis, for sampling BSDFs
+    DVec3::new(fastrand::f64(), fastrand::f64(), fastrand::f64())
+}
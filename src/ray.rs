@@ -4,6 +4,8 @@ use glam::DVec3;
 pub struct Ray {
     pub origin: DVec3,
     pub direction: DVec3,
+    // When the ray was sent during the shutter interval, for motion blur
+    pub time: f64,
 }
 
 impl Ray {
@@ -11,9 +13,15 @@ impl Ray {
         Self {
             origin,
             direction: direction.normalize(),
+            time: 0.0,
         }
     }
 
+    pub fn with_time(mut self, time: f64) -> Self {
+        self.time = time;
+        self
+    }
+
     pub fn at(&self, distance: f64) -> DVec3 {
         self.origin + self.direction * distance
     }
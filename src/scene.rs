@@ -0,0 +1,134 @@
+use std::sync::Arc;
+
+use glam::DVec2;
+
+use crate::environment::{Environment, Gradient};
+use crate::hittable::{Hit, Hittable, HittableList};
+use crate::material::Material;
+use crate::random::random_exponential;
+use crate::ray::Ray;
+use crate::volume::Volume;
+
+// Homogeneous medium filling the whole scene, e.g. fog or haze for god rays
+pub struct Atmosphere {
+    density: f64,
+    phase: Arc<dyn Material>,
+    extent: f64,
+}
+
+impl Atmosphere {
+    // Use Isotropic or HenyeyGreenstein for phase
+    pub fn new(density: f64, phase: Arc<dyn Material>) -> Self {
+        Atmosphere {
+            density,
+            phase,
+            extent: f64::INFINITY,
+        }
+    }
+
+    // How far each ray travels through the atmosphere, so the environment
+    // and distant objects stay visible. Infinite hides the environment
+    pub fn with_extent(mut self, extent: f64) -> Self {
+        self.extent = extent;
+        self
+    }
+}
+
+impl Volume for Atmosphere {
+    fn sample_distance(&self, _ray: &Ray, min: f64, max: f64) -> Option<f64> {
+        let distance = min + random_exponential(self.density);
+        (distance < max.min(min + self.extent)).then_some(distance)
+    }
+
+    fn transmittance(&self, _ray: &Ray, min: f64, max: f64) -> f64 {
+        let length = (max - min).min(self.extent);
+        (-self.density * length).exp()
+    }
+
+    fn phase(&self) -> Arc<dyn Material> {
+        self.phase.clone()
+    }
+}
+
+pub struct Scene {
+    pub world: Box<dyn Hittable>,
+
+    // Emissive objects sampled directly, these should also be in world
+    pub lights: HittableList,
+
+    // Seen by rays that miss everything
+    pub environment: Box<dyn Environment>,
+
+    pub atmosphere: Option<Atmosphere>,
+
+    // Media such as smoke grids that aren't part of world
+    pub volumes: Vec<Box<dyn Volume>>,
+}
+
+impl Scene {
+    pub fn new<T: Hittable + 'static>(world: T) -> Self {
+        Scene {
+            world: Box::new(world),
+            lights: HittableList::new(),
+            environment: Box::new(Gradient::sky()),
+            atmosphere: None,
+            volumes: vec![],
+        }
+    }
+
+    pub fn with_environment<T: Environment + 'static>(mut self, environment: T) -> Self {
+        self.environment = Box::new(environment);
+        self
+    }
+
+    pub fn with_atmosphere(mut self, atmosphere: Atmosphere) -> Self {
+        self.atmosphere = Some(atmosphere);
+        self
+    }
+
+    pub fn add_light(&mut self, light: Arc<dyn Hittable>) {
+        self.lights.add_shared(light);
+    }
+
+    pub fn add_volume<T: Volume + 'static>(&mut self, volume: T) {
+        self.volumes.push(Box::new(volume));
+    }
+
+    fn all_volumes(&self) -> impl Iterator<Item = &dyn Volume> {
+        self.atmosphere
+            .iter()
+            .map(|x| x as &dyn Volume)
+            .chain(self.volumes.iter().map(|x| x.as_ref()))
+    }
+
+    // The world with volumes scattering rays before they reach it
+    pub fn hit(&self, ray: &Ray, min: f64, max: f64) -> Option<Hit> {
+        let mut hit = self.world.hit(ray, min, max);
+        let mut end = hit.as_ref().map_or(max, |hit| hit.distance);
+
+        // The closest scattering event out of all the volumes wins
+        for volume in self.all_volumes() {
+            if let Some(distance) = volume.sample_distance(ray, min, end) {
+                end = distance;
+                hit = Some(Hit::new(
+                    ray.at(distance),
+                    -ray.direction,
+                    distance,
+                    true,
+                    DVec2::ZERO,
+                    volume.phase(),
+                ));
+            }
+        }
+
+        hit
+    }
+
+    // Fraction of light passing through the volumes, world objects are
+    // ignored so check those with world.hit first
+    pub fn transmittance(&self, ray: &Ray, min: f64, max: f64) -> f64 {
+        self.all_volumes()
+            .map(|volume| volume.transmittance(ray, min, max))
+            .product()
+    }
+}
@@ -0,0 +1,563 @@
+use glam::{dvec3, DAffine3, DQuat, DVec3, Vec3Swizzles};
+
+use super::orbittrap::OrbitTrap;
+use super::traits::Sdf;
+use crate::hittable::Aabb;
+
+fn mix(a: f64, b: f64, t: f64) -> f64 {
+    a + (b - a) * t
+}
+
+// Polynomial smooth minimum, k is roughly the size of the blend region
+fn smooth_min(a: f64, b: f64, k: f64) -> f64 {
+    let h = (0.5 + 0.5 * (b - a) / k).clamp(0.0, 1.0);
+    mix(b, a, h) - k * h * (1.0 - h)
+}
+
+pub struct Union<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Sdf, B: Sdf> Union<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Union { a, b }
+    }
+}
+
+impl<A: Sdf, B: Sdf> Sdf for Union<A, B> {
+    fn distance(&self, point: DVec3) -> f64 {
+        self.a.distance(point).min(self.b.distance(point))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.a.bounding_box().union(&self.b.bounding_box())
+    }
+
+    // From whichever surface is closer
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        if self.a.distance(point) <= self.b.distance(point) {
+            self.a.orbit_trap(point)
+        } else {
+            self.b.orbit_trap(point)
+        }
+    }
+}
+
+pub struct Intersection<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Sdf, B: Sdf> Intersection<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Intersection { a, b }
+    }
+}
+
+impl<A: Sdf, B: Sdf> Sdf for Intersection<A, B> {
+    fn distance(&self, point: DVec3) -> f64 {
+        self.a.distance(point).max(self.b.distance(point))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.a.bounding_box().intersection(&self.b.bounding_box())
+    }
+
+    // From whichever surface bounds the result
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        if self.a.distance(point) >= self.b.distance(point) {
+            self.a.orbit_trap(point)
+        } else {
+            self.b.orbit_trap(point)
+        }
+    }
+}
+
+// a with b cut out of it
+pub struct Difference<A, B> {
+    a: A,
+    b: B,
+}
+
+impl<A: Sdf, B: Sdf> Difference<A, B> {
+    pub fn new(a: A, b: B) -> Self {
+        Difference { a, b }
+    }
+}
+
+impl<A: Sdf, B: Sdf> Sdf for Difference<A, B> {
+    fn distance(&self, point: DVec3) -> f64 {
+        self.a.distance(point).max(-self.b.distance(point))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.a.bounding_box()
+    }
+
+    // Inside the cut the surface belongs to b
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        if self.a.distance(point) >= -self.b.distance(point) {
+            self.a.orbit_trap(point)
+        } else {
+            self.b.orbit_trap(point)
+        }
+    }
+}
+
+pub struct SmoothUnion<A, B> {
+    a: A,
+    b: B,
+    k: f64,
+}
+
+impl<A: Sdf, B: Sdf> SmoothUnion<A, B> {
+    pub fn new(a: A, b: B, k: f64) -> Self {
+        SmoothUnion { a, b, k }
+    }
+}
+
+impl<A: Sdf, B: Sdf> Sdf for SmoothUnion<A, B> {
+    fn distance(&self, point: DVec3) -> f64 {
+        smooth_min(self.a.distance(point), self.b.distance(point), self.k)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // The blend bulges out by at most k / 4
+        self.a
+            .bounding_box()
+            .union(&self.b.bounding_box())
+            .pad(self.k / 4.0)
+    }
+
+    // No blending, from whichever surface is closer
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        if self.a.distance(point) <= self.b.distance(point) {
+            self.a.orbit_trap(point)
+        } else {
+            self.b.orbit_trap(point)
+        }
+    }
+}
+
+pub struct SmoothIntersection<A, B> {
+    a: A,
+    b: B,
+    k: f64,
+}
+
+impl<A: Sdf, B: Sdf> SmoothIntersection<A, B> {
+    pub fn new(a: A, b: B, k: f64) -> Self {
+        SmoothIntersection { a, b, k }
+    }
+}
+
+impl<A: Sdf, B: Sdf> Sdf for SmoothIntersection<A, B> {
+    fn distance(&self, point: DVec3) -> f64 {
+        -smooth_min(-self.a.distance(point), -self.b.distance(point), self.k)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        // Smoothing only ever removes material
+        self.a.bounding_box().intersection(&self.b.bounding_box())
+    }
+
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        if self.a.distance(point) >= self.b.distance(point) {
+            self.a.orbit_trap(point)
+        } else {
+            self.b.orbit_trap(point)
+        }
+    }
+}
+
+pub struct SmoothDifference<A, B> {
+    a: A,
+    b: B,
+    k: f64,
+}
+
+impl<A: Sdf, B: Sdf> SmoothDifference<A, B> {
+    pub fn new(a: A, b: B, k: f64) -> Self {
+        SmoothDifference { a, b, k }
+    }
+}
+
+impl<A: Sdf, B: Sdf> Sdf for SmoothDifference<A, B> {
+    fn distance(&self, point: DVec3) -> f64 {
+        -smooth_min(-self.a.distance(point), self.b.distance(point), self.k)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.a.bounding_box()
+    }
+
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        if self.a.distance(point) >= -self.b.distance(point) {
+            self.a.orbit_trap(point)
+        } else {
+            self.b.orbit_trap(point)
+        }
+    }
+}
+
+// Infinite copies of sdf spaced by period, zero components aren't repeated
+// The sdf should fit inside one cell or the distances will be wrong
+pub struct Repeat<S> {
+    sdf: S,
+    period: DVec3,
+    limit: Option<DVec3>,
+}
+
+impl<S: Sdf> Repeat<S> {
+    pub fn new(sdf: S, period: DVec3) -> Self {
+        Repeat {
+            sdf,
+            period,
+            limit: None,
+        }
+    }
+
+    // Only limit copies either side of the original on each axis
+    pub fn with_limit(mut self, limit: DVec3) -> Self {
+        self.limit = Some(limit.round());
+        self
+    }
+
+    // Point relative to the nearest copy
+    fn local(&self, point: DVec3) -> DVec3 {
+        let repeats = self.period.cmpne(DVec3::ZERO);
+        let cell = DVec3::select(repeats, point / self.period, DVec3::ZERO).round();
+        let cell = match self.limit {
+            Some(limit) => cell.clamp(-limit, limit),
+            None => cell,
+        };
+
+        point - self.period * cell
+    }
+}
+
+impl<S: Sdf> Sdf for Repeat<S> {
+    fn distance(&self, point: DVec3) -> f64 {
+        self.sdf.distance(self.local(point))
+    }
+
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        self.sdf.orbit_trap(self.local(point))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let Some(limit) = self.limit else {
+            return Aabb::INFINITE;
+        };
+
+        let inner = self.sdf.bounding_box();
+        let offset = (self.period * limit).abs();
+        Aabb::new(inner.min - offset, inner.max + offset)
+    }
+}
+
+// Rotates around the y axis by k radians per unit of height
+pub struct Twist<S> {
+    sdf: S,
+    k: f64,
+}
+
+impl<S: Sdf> Twist<S> {
+    pub fn new(sdf: S, k: f64) -> Self {
+        Twist { sdf, k }
+    }
+
+    fn twisted(&self, point: DVec3) -> DVec3 {
+        let (sin, cos) = (self.k * point.y).sin_cos();
+        dvec3(
+            cos * point.x - sin * point.z,
+            point.y,
+            sin * point.x + cos * point.z,
+        )
+    }
+}
+
+impl<S: Sdf> Sdf for Twist<S> {
+    fn distance(&self, point: DVec3) -> f64 {
+        // The twist stretches space further from the axis, so scale the
+        // distance down to stop marching overshooting
+        let stretch = (1.0 + (self.k * point.xz().length()).powi(2)).sqrt();
+        self.sdf.distance(self.twisted(point)) / stretch
+    }
+
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        self.sdf.orbit_trap(self.twisted(point))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let inner = self.sdf.bounding_box();
+
+        if !inner.is_finite() {
+            return Aabb::INFINITE;
+        }
+
+        // Any rotation about y stays inside the cylinder around the box
+        let radius = inner.min.xz().abs().max(inner.max.xz().abs()).length();
+        Aabb::new(
+            dvec3(-radius, inner.min.y, -radius),
+            dvec3(radius, inner.max.y, radius),
+        )
+    }
+}
+
+// Bends the xy plane around the z axis by k radians per unit along x
+pub struct Bend<S> {
+    sdf: S,
+    k: f64,
+}
+
+impl<S: Sdf> Bend<S> {
+    pub fn new(sdf: S, k: f64) -> Self {
+        Bend { sdf, k }
+    }
+
+    fn bent(&self, point: DVec3) -> DVec3 {
+        let (sin, cos) = (self.k * point.x).sin_cos();
+        dvec3(
+            cos * point.x - sin * point.y,
+            sin * point.x + cos * point.y,
+            point.z,
+        )
+    }
+}
+
+impl<S: Sdf> Sdf for Bend<S> {
+    fn distance(&self, point: DVec3) -> f64 {
+        let stretch = (1.0 + (self.k * point.xy().length()).powi(2)).sqrt();
+        self.sdf.distance(self.bent(point)) / stretch
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let inner = self.sdf.bounding_box();
+
+        if !inner.is_finite() {
+            return Aabb::INFINITE;
+        }
+
+        // Bending rotates about z so points keep their distance from the axis
+        let radius = inner.min.xy().abs().max(inner.max.xy().abs()).length();
+        Aabb::new(
+            dvec3(-radius, -radius, inner.min.z),
+            dvec3(radius, radius, inner.max.z),
+        )
+    }
+
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        self.sdf.orbit_trap(self.bent(point))
+    }
+}
+
+// Hollows out sdf leaving a shell of thickness around its surface
+pub struct Onion<S> {
+    sdf: S,
+    thickness: f64,
+}
+
+impl<S: Sdf> Onion<S> {
+    pub fn new(sdf: S, thickness: f64) -> Self {
+        Onion { sdf, thickness }
+    }
+}
+
+impl<S: Sdf> Sdf for Onion<S> {
+    fn distance(&self, point: DVec3) -> f64 {
+        self.sdf.distance(point).abs() - self.thickness
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.sdf.bounding_box().pad(self.thickness)
+    }
+
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        self.sdf.orbit_trap(point)
+    }
+}
+
+// Only uniform scales keep distances correct, negative ones mirror the shape
+pub struct Transform<S> {
+    sdf: S,
+    matrix: DAffine3,
+    inverse: DAffine3,
+    scale: f64,
+}
+
+impl<S: Sdf> Transform<S> {
+    pub fn new(sdf: S, scale: f64, rotation: DQuat, translation: DVec3) -> Self {
+        let matrix =
+            DAffine3::from_scale_rotation_translation(DVec3::splat(scale), rotation, translation);
+
+        Transform {
+            sdf,
+            matrix,
+            inverse: matrix.inverse(),
+            scale,
+        }
+    }
+}
+
+impl<S: Sdf> Sdf for Transform<S> {
+    fn distance(&self, point: DVec3) -> f64 {
+        self.sdf.distance(self.inverse.transform_point3(point)) * self.scale.abs()
+    }
+
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        self.sdf.orbit_trap(self.inverse.transform_point3(point))
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        self.sdf.bounding_box().transform(&self.matrix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hittable::Mandelbulb;
+    use crate::sdf::{Cuboid, SdfExt, Sphere};
+    use crate::vec::approx_eq;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn boolean_operations() {
+        let a = Sphere::new(1.0);
+        let b = Sphere::new(1.0).translate(dvec3(1.0, 0.0, 0.0));
+        let point = dvec3(-0.5, 0.0, 0.0);
+
+        assert!(close(
+            Sphere::new(1.0).union(Sphere::new(2.0)).distance(point),
+            -1.5
+        ));
+        assert!(close(a.intersection(b).distance(point), 0.5));
+
+        let cut = Sphere::new(1.0).difference(Sphere::new(0.5));
+        assert!(close(cut.distance(DVec3::ZERO), 0.5));
+        assert!(close(cut.distance(dvec3(0.75, 0.0, 0.0)), -0.25));
+    }
+
+    #[test]
+    fn smooth_union_blends() {
+        let a = Sphere::new(1.0).translate(dvec3(-1.0, 0.0, 0.0));
+        let b = Sphere::new(1.0).translate(dvec3(1.0, 0.0, 0.0));
+        let point = dvec3(0.0, 1.0, 0.0);
+
+        let sharp = Sphere::new(1.0)
+            .translate(dvec3(-1.0, 0.0, 0.0))
+            .union(Sphere::new(1.0).translate(dvec3(1.0, 0.0, 0.0)))
+            .distance(point);
+        let smooth = a.smooth_union(b, 0.5).distance(point);
+
+        assert!(smooth < sharp);
+        // Far from the blend region nothing changes
+        let far =
+            Sphere::new(1.0).smooth_union(Sphere::new(1.0).translate(dvec3(10.0, 0.0, 0.0)), 0.5);
+        assert!(close(far.distance(dvec3(-2.0, 0.0, 0.0)), 1.0));
+    }
+
+    #[test]
+    fn repeat_copies() {
+        let spheres = Sphere::new(0.5).repeat(dvec3(2.0, 0.0, 0.0));
+
+        assert!(close(spheres.distance(dvec3(10.0, 0.0, 0.0)), -0.5));
+        assert!(close(spheres.distance(dvec3(11.0, 0.0, 0.0)), 0.5));
+        assert!(close(spheres.distance(dvec3(10.0, 2.0, 0.0)), 1.5));
+
+        let limited = Repeat::new(Sphere::new(0.5), dvec3(2.0, 0.0, 0.0)).with_limit(DVec3::ONE);
+        assert!(close(limited.distance(dvec3(6.0, 0.0, 0.0)), 3.5));
+        assert!(approx_eq(limited.bounding_box().max, dvec3(2.5, 0.5, 0.5)));
+    }
+
+    #[test]
+    fn transform_scales_distance() {
+        let sphere = Sphere::new(1.0).scale(2.0).translate(dvec3(0.0, 5.0, 0.0));
+
+        assert!(close(sphere.distance(dvec3(0.0, 10.0, 0.0)), 3.0));
+
+        let bounds = sphere.bounding_box();
+        assert!(approx_eq(bounds.min, dvec3(-2.0, 3.0, -2.0)));
+        assert!(approx_eq(bounds.max, dvec3(2.0, 7.0, 2.0)));
+
+        let mirrored = Sphere::new(1.0).scale(-2.0);
+        assert!(close(mirrored.distance(dvec3(0.0, 5.0, 0.0)), 3.0));
+    }
+
+    #[test]
+    fn rotation_and_onion() {
+        let rotated = Cuboid::new(dvec3(2.0, 0.5, 0.5))
+            .rotate(DQuat::from_rotation_z(std::f64::consts::FRAC_PI_2));
+
+        assert!(close(rotated.distance(dvec3(0.0, 3.0, 0.0)), 1.0));
+
+        let shell = Sphere::new(1.0).onion(0.1);
+        assert!(close(shell.distance(DVec3::ZERO), 0.9));
+        assert!(close(shell.distance(dvec3(1.0, 0.0, 0.0)), -0.1));
+    }
+
+    #[test]
+    fn twist_keeps_axis() {
+        let twisted = Cuboid::new(dvec3(1.0, 2.0, 1.0)).twist(1.0);
+
+        assert!(close(twisted.distance(DVec3::ZERO), -1.0));
+        assert!(twisted.bounding_box().max.x >= 2.0f64.sqrt() - 1e-9);
+    }
+
+    #[test]
+    fn bend_stays_in_bounds() {
+        let bent = Cuboid::new(dvec3(2.0, 0.25, 0.5)).bend(0.8);
+        let bounds = bent.bounding_box();
+        assert!(bounds.is_finite());
+
+        for i in 0..=40 {
+            for j in 0..=40 {
+                let point = dvec3(i as f64, j as f64, 0.0) * 0.2 - dvec3(4.0, 4.0, 0.0);
+
+                if bent.distance(point) <= 0.0 {
+                    assert_eq!(bounds.grow(point), bounds, "{point}");
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn orbit_traps_pass_through() {
+        let bulb = || Mandelbulb::new(8.0);
+        let point = dvec3(0.25, 0.75, -0.375);
+        let expected = bulb().orbit_trap(point);
+        assert!(expected.is_some());
+
+        // Warps hand the warped point on
+        let repeated = bulb().repeat(dvec3(4.0, 0.0, 0.0));
+        assert_eq!(repeated.orbit_trap(point + dvec3(8.0, 0.0, 0.0)), expected);
+        assert_eq!(bulb().onion(0.1).orbit_trap(point), expected);
+        assert_eq!(bulb().twist(0.0).orbit_trap(point), expected);
+        assert!(bulb().bend(0.5).orbit_trap(point).is_some());
+
+        // Booleans hand on the operand whose surface is used
+        assert_eq!(
+            bulb().intersection(Sphere::new(10.0)).orbit_trap(point),
+            expected
+        );
+        assert_eq!(
+            Sphere::new(10.0).difference(bulb()).orbit_trap(point),
+            expected
+        );
+        assert_eq!(
+            bulb()
+                .smooth_intersection(Sphere::new(10.0), 0.1)
+                .orbit_trap(point),
+            expected
+        );
+        assert_eq!(
+            bulb().difference(Sphere::new(0.1)).orbit_trap(point),
+            expected
+        );
+    }
+}
@@ -0,0 +1,12 @@
+pub mod combinators;
+pub mod orbittrap;
+pub mod primitives;
+pub mod traits;
+
+pub use combinators::{
+    Bend, Difference, Intersection, Onion, Repeat, SmoothDifference, SmoothIntersection,
+    SmoothUnion, Transform, Twist, Union,
+};
+pub use orbittrap::{OrbitTrap, Traps};
+pub use primitives::{Capsule, Cuboid, Cylinder, Plane, RoundedCuboid, Sphere, Torus};
+pub use traits::{Sdf, SdfExt};
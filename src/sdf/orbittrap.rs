@@ -0,0 +1,64 @@
+use glam::DVec3;
+
+// Shapes an orbit is measured against while iterating
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Traps {
+    pub point: DVec3,
+    // Plane of points p with p . normal = offset
+    pub normal: DVec3,
+    pub offset: f64,
+    // Line through the origin
+    pub axis: DVec3,
+}
+
+impl Default for Traps {
+    fn default() -> Self {
+        Traps {
+            point: DVec3::ZERO,
+            normal: DVec3::Y,
+            offset: 0.0,
+            axis: DVec3::Y,
+        }
+    }
+}
+
+// What happened to the orbit of a point on a fractal surface, for colouring
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitTrap {
+    // Closest the orbit came to each of the traps
+    pub point: f64,
+    pub plane: f64,
+    pub axis: f64,
+    // Iterations before escaping, the maximum if it never did
+    pub iterations: usize,
+    // Continuous version of iterations without banding
+    pub smooth: f64,
+}
+
+impl OrbitTrap {
+    pub fn new() -> Self {
+        OrbitTrap {
+            point: f64::INFINITY,
+            plane: f64::INFINITY,
+            axis: f64::INFINITY,
+            iterations: 0,
+            smooth: 0.0,
+        }
+    }
+
+    pub fn update(&mut self, z: DVec3, traps: &Traps) {
+        let axis = traps.axis.normalize();
+
+        self.point = self.point.min(z.distance(traps.point));
+        self.plane = self
+            .plane
+            .min((z.dot(traps.normal.normalize()) - traps.offset).abs());
+        self.axis = self.axis.min(z.reject_from_normalized(axis).length());
+    }
+}
+
+impl Default for OrbitTrap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,244 @@
+use glam::{dvec2, DVec2, DVec3, Vec3Swizzles};
+
+use super::traits::Sdf;
+use crate::hittable::Aabb;
+
+// Most of these are from Inigo Quilez's distance functions article
+// All are centred on the origin, move them with Transform
+
+pub struct Sphere {
+    radius: f64,
+}
+
+impl Sphere {
+    pub fn new(radius: f64) -> Self {
+        Sphere { radius }
+    }
+}
+
+impl Sdf for Sphere {
+    fn distance(&self, point: DVec3) -> f64 {
+        point.length() - self.radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(DVec3::splat(-self.radius), DVec3::splat(self.radius))
+    }
+}
+
+// Box, but that name is taken
+pub struct Cuboid {
+    half_extents: DVec3,
+}
+
+impl Cuboid {
+    pub fn new(half_extents: DVec3) -> Self {
+        Cuboid { half_extents }
+    }
+}
+
+fn cuboid(point: DVec3, half_extents: DVec3) -> f64 {
+    let q = point.abs() - half_extents;
+    q.max(DVec3::ZERO).length() + q.max_element().min(0.0)
+}
+
+impl Sdf for Cuboid {
+    fn distance(&self, point: DVec3) -> f64 {
+        cuboid(point, self.half_extents)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(-self.half_extents, self.half_extents)
+    }
+}
+
+// Same outer size as a Cuboid with the same half extents
+pub struct RoundedCuboid {
+    half_extents: DVec3,
+    radius: f64,
+}
+
+impl RoundedCuboid {
+    pub fn new(half_extents: DVec3, radius: f64) -> Self {
+        RoundedCuboid {
+            half_extents,
+            radius,
+        }
+    }
+}
+
+impl Sdf for RoundedCuboid {
+    fn distance(&self, point: DVec3) -> f64 {
+        cuboid(point, self.half_extents - self.radius) - self.radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(-self.half_extents, self.half_extents)
+    }
+}
+
+// Lies in the xz plane
+pub struct Torus {
+    major_radius: f64,
+    minor_radius: f64,
+}
+
+impl Torus {
+    pub fn new(major_radius: f64, minor_radius: f64) -> Self {
+        Torus {
+            major_radius,
+            minor_radius,
+        }
+    }
+}
+
+impl Sdf for Torus {
+    fn distance(&self, point: DVec3) -> f64 {
+        let q = dvec2(point.xz().length() - self.major_radius, point.y);
+        q.length() - self.minor_radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let r = self.major_radius + self.minor_radius;
+        let extent = DVec3::new(r, self.minor_radius, r);
+        Aabb::new(-extent, extent)
+    }
+}
+
+// Line segment from a to b with thickness
+pub struct Capsule {
+    a: DVec3,
+    b: DVec3,
+    radius: f64,
+}
+
+impl Capsule {
+    pub fn new(a: DVec3, b: DVec3, radius: f64) -> Self {
+        Capsule { a, b, radius }
+    }
+}
+
+impl Sdf for Capsule {
+    fn distance(&self, point: DVec3) -> f64 {
+        let pa = point - self.a;
+        let ba = self.b - self.a;
+        let h = (pa.dot(ba) / ba.length_squared()).clamp(0.0, 1.0);
+        (pa - ba * h).length() - self.radius
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        Aabb::new(self.a, self.b).pad(self.radius)
+    }
+}
+
+// Capped, along the y axis
+pub struct Cylinder {
+    radius: f64,
+    half_height: f64,
+}
+
+impl Cylinder {
+    pub fn new(radius: f64, half_height: f64) -> Self {
+        Cylinder {
+            radius,
+            half_height,
+        }
+    }
+}
+
+impl Sdf for Cylinder {
+    fn distance(&self, point: DVec3) -> f64 {
+        let d = dvec2(point.xz().length(), point.y.abs()) - dvec2(self.radius, self.half_height);
+        d.max_element().min(0.0) + d.max(DVec2::ZERO).length()
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        let extent = DVec3::new(self.radius, self.half_height, self.radius);
+        Aabb::new(-extent, extent)
+    }
+}
+
+// Everything below the plane normal . p = offset is inside
+pub struct Plane {
+    normal: DVec3,
+    offset: f64,
+}
+
+impl Plane {
+    pub fn new(normal: DVec3, offset: f64) -> Self {
+        Plane {
+            normal: normal.normalize(),
+            offset,
+        }
+    }
+}
+
+impl Sdf for Plane {
+    fn distance(&self, point: DVec3) -> f64 {
+        point.dot(self.normal) - self.offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn sphere_distance() {
+        let sphere = Sphere::new(2.0);
+
+        assert!(close(sphere.distance(dvec3(3.0, 0.0, 0.0)), 1.0));
+        assert!(close(sphere.distance(DVec3::ZERO), -2.0));
+    }
+
+    #[test]
+    fn cuboid_distance() {
+        let cuboid = Cuboid::new(DVec3::ONE);
+
+        assert!(close(cuboid.distance(dvec3(2.0, 0.0, 0.0)), 1.0));
+        assert!(close(cuboid.distance(dvec3(2.0, 2.0, 1.0)), 2.0f64.sqrt()));
+        assert!(close(cuboid.distance(dvec3(0.5, 0.0, 0.0)), -0.5));
+    }
+
+    #[test]
+    fn rounded_cuboid_keeps_size() {
+        let rounded = RoundedCuboid::new(DVec3::ONE, 0.25);
+
+        assert!(close(rounded.distance(dvec3(2.0, 0.0, 0.0)), 1.0));
+        assert!(rounded.distance(dvec3(1.0, 1.0, 0.0)) > 0.0);
+    }
+
+    #[test]
+    fn torus_distance() {
+        let torus = Torus::new(2.0, 0.5);
+
+        assert!(close(torus.distance(dvec3(2.0, 0.0, 0.0)), -0.5));
+        assert!(close(torus.distance(DVec3::ZERO), 1.5));
+        assert!(close(torus.distance(dvec3(0.0, 1.0, -2.0)), 0.5));
+    }
+
+    #[test]
+    fn capsule_and_cylinder() {
+        let capsule = Capsule::new(DVec3::ZERO, dvec3(0.0, 2.0, 0.0), 0.5);
+        let cylinder = Cylinder::new(0.5, 1.0);
+
+        assert!(close(capsule.distance(dvec3(0.0, 3.0, 0.0)), 0.5));
+        assert!(close(capsule.distance(dvec3(1.0, 1.0, 0.0)), 0.5));
+        assert!(close(cylinder.distance(dvec3(0.0, 3.0, 0.0)), 2.0));
+        assert!(close(cylinder.distance(dvec3(1.0, 0.0, 0.0)), 0.5));
+    }
+
+    #[test]
+    fn plane_distance() {
+        let plane = Plane::new(dvec3(0.0, 2.0, 0.0), 1.0);
+
+        assert!(close(plane.distance(dvec3(5.0, 3.0, -2.0)), 2.0));
+        assert!(plane.bounding_box() == Aabb::INFINITE);
+    }
+}
@@ -0,0 +1,116 @@
+use std::sync::Arc;
+
+use glam::{DQuat, DVec3};
+
+use super::combinators::{
+    Bend, Difference, Intersection, Onion, Repeat, SmoothDifference, SmoothIntersection,
+    SmoothUnion, Transform, Twist, Union,
+};
+use super::orbittrap::OrbitTrap;
+use crate::hittable::Aabb;
+
+// Signed distance field, negative inside
+// Estimates are fine as long as they never overshoot the surface
+pub trait Sdf: Send + Sync {
+    fn distance(&self, point: DVec3) -> f64;
+
+    // Region outside of which the distance is always positive
+    fn bounding_box(&self) -> Aabb {
+        Aabb::INFINITE
+    }
+
+    // Colouring data for fractals, only computed once a surface is hit
+    fn orbit_trap(&self, _point: DVec3) -> Option<OrbitTrap> {
+        None
+    }
+}
+
+impl<S: Sdf + ?Sized> Sdf for Arc<S> {
+    fn distance(&self, point: DVec3) -> f64 {
+        (**self).distance(point)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        (**self).orbit_trap(point)
+    }
+}
+
+impl<S: Sdf + ?Sized> Sdf for Box<S> {
+    fn distance(&self, point: DVec3) -> f64 {
+        (**self).distance(point)
+    }
+
+    fn bounding_box(&self) -> Aabb {
+        (**self).bounding_box()
+    }
+
+    fn orbit_trap(&self, point: DVec3) -> Option<OrbitTrap> {
+        (**self).orbit_trap(point)
+    }
+}
+
+// Chainable combinators, e.g. sphere.difference(cuboid).translate(offset)
+pub trait SdfExt: Sdf + Sized {
+    fn union<B: Sdf>(self, other: B) -> Union<Self, B> {
+        Union::new(self, other)
+    }
+
+    fn intersection<B: Sdf>(self, other: B) -> Intersection<Self, B> {
+        Intersection::new(self, other)
+    }
+
+    fn difference<B: Sdf>(self, other: B) -> Difference<Self, B> {
+        Difference::new(self, other)
+    }
+
+    fn smooth_union<B: Sdf>(self, other: B, k: f64) -> SmoothUnion<Self, B> {
+        SmoothUnion::new(self, other, k)
+    }
+
+    fn smooth_intersection<B: Sdf>(self, other: B, k: f64) -> SmoothIntersection<Self, B> {
+        SmoothIntersection::new(self, other, k)
+    }
+
+    fn smooth_difference<B: Sdf>(self, other: B, k: f64) -> SmoothDifference<Self, B> {
+        SmoothDifference::new(self, other, k)
+    }
+
+    fn repeat(self, period: DVec3) -> Repeat<Self> {
+        Repeat::new(self, period)
+    }
+
+    fn twist(self, k: f64) -> Twist<Self> {
+        Twist::new(self, k)
+    }
+
+    fn bend(self, k: f64) -> Bend<Self> {
+        Bend::new(self, k)
+    }
+
+    fn onion(self, thickness: f64) -> Onion<Self> {
+        Onion::new(self, thickness)
+    }
+
+    // Scales, then rotates, then translates
+    fn transform(self, scale: f64, rotation: DQuat, translation: DVec3) -> Transform<Self> {
+        Transform::new(self, scale, rotation, translation)
+    }
+
+    fn translate(self, offset: DVec3) -> Transform<Self> {
+        Transform::new(self, 1.0, DQuat::IDENTITY, offset)
+    }
+
+    fn rotate(self, rotation: DQuat) -> Transform<Self> {
+        Transform::new(self, 1.0, rotation, DVec3::ZERO)
+    }
+
+    fn scale(self, scale: f64) -> Transform<Self> {
+        Transform::new(self, scale, DQuat::IDENTITY, DVec3::ZERO)
+    }
+}
+
+impl<S: Sdf> SdfExt for S {}
@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use super::solid::SolidColour;
+use super::traits::Texture;
+use crate::hittable::Hit;
+
+// Alternates between two textures in 3D cells of size scale
+pub struct Checker {
+    inv_scale: f64,
+    even: Arc<dyn Texture>,
+    odd: Arc<dyn Texture>,
+}
+
+impl Checker {
+    pub fn new(scale: f64, even: Arc<dyn Texture>, odd: Arc<dyn Texture>) -> Self {
+        Checker {
+            inv_scale: 1.0 / scale,
+            even,
+            odd,
+        }
+    }
+
+    pub fn from_colours(scale: f64, even: DVec3, odd: DVec3) -> Self {
+        Checker::new(
+            scale,
+            Arc::new(SolidColour::new(even)),
+            Arc::new(SolidColour::new(odd)),
+        )
+    }
+}
+
+impl Texture for Checker {
+    fn value(&self, hit: &Hit) -> DVec3 {
+        let cell = (hit.point * self.inv_scale).floor();
+        let sum = cell.x as i64 + cell.y as i64 + cell.z as i64;
+
+        if sum.rem_euclid(2) == 0 {
+            self.even.value(hit)
+        } else {
+            self.odd.value(hit)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::{dvec3, DVec2};
+
+    use super::*;
+    use crate::material::Lambertian;
+
+    fn at(point: DVec3) -> Hit {
+        let material = Arc::new(Lambertian::new(DVec3::ONE));
+        Hit::new(point, DVec3::Y, 1.0, true, DVec2::ZERO, material)
+    }
+
+    #[test]
+    fn alternates_cells() {
+        let checker = Checker::from_colours(0.5, DVec3::ONE, DVec3::ZERO);
+
+        assert_eq!(checker.value(&at(dvec3(0.1, 0.1, 0.1))), DVec3::ONE);
+        assert_eq!(checker.value(&at(dvec3(0.6, 0.1, 0.1))), DVec3::ZERO);
+        assert_eq!(checker.value(&at(dvec3(0.6, -0.1, 0.1))), DVec3::ONE);
+    }
+}
@@ -0,0 +1,170 @@
+use std::path::Path;
+
+use glam::{dvec3, DVec3};
+use image::{DynamicImage, ImageResult, Rgb32FImage};
+
+use super::traits::Texture;
+use crate::hittable::Hit;
+
+// What happens to uvs outside [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WrapMode {
+    Repeat,
+    Mirror,
+    Clamp,
+}
+
+impl WrapMode {
+    fn apply(&self, i: i64, size: u32) -> u32 {
+        let size = size as i64;
+
+        let out = match self {
+            WrapMode::Repeat => i.rem_euclid(size),
+            WrapMode::Mirror => {
+                let period = i.rem_euclid(2 * size);
+
+                if period < size {
+                    period
+                } else {
+                    2 * size - 1 - period
+                }
+            }
+            WrapMode::Clamp => i.clamp(0, size - 1),
+        };
+
+        out as u32
+    }
+}
+
+fn srgb_to_linear(x: f32) -> f32 {
+    if x <= 0.04045 {
+        x / 12.92
+    } else {
+        ((x + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+// Bilinearly filtered image with v = 0 at the bottom
+pub struct ImageTexture {
+    image: Rgb32FImage,
+    wrap: WrapMode,
+}
+
+impl ImageTexture {
+    // Pixels should already be linear
+    pub fn new(image: Rgb32FImage) -> Self {
+        ImageTexture {
+            image,
+            wrap: WrapMode::Repeat,
+        }
+    }
+
+    // 8 and 16 bit images are assumed to be sRGB encoded, float images linear
+    pub fn load(path: impl AsRef<Path>) -> ImageResult<Self> {
+        let image = image::open(path)?;
+
+        let linear = matches!(
+            image,
+            DynamicImage::ImageRgb32F(_) | DynamicImage::ImageRgba32F(_)
+        );
+
+        let mut image = image.into_rgb32f();
+
+        if !linear {
+            image
+                .pixels_mut()
+                .for_each(|pixel| pixel.0 = pixel.0.map(srgb_to_linear));
+        }
+
+        Ok(ImageTexture::new(image))
+    }
+
+    pub fn with_wrap(mut self, wrap: WrapMode) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    fn texel(&self, x: i64, y: i64) -> DVec3 {
+        let (width, height) = self.image.dimensions();
+        let [r, g, b] = self
+            .image
+            .get_pixel(self.wrap.apply(x, width), self.wrap.apply(y, height))
+            .0;
+
+        dvec3(r as f64, g as f64, b as f64)
+    }
+}
+
+impl Texture for ImageTexture {
+    fn value(&self, hit: &Hit) -> DVec3 {
+        let (width, height) = self.image.dimensions();
+        let uv = hit.uv;
+
+        // Pixel centres are at half integers
+        let x = uv.x * width as f64 - 0.5;
+        let y = (1.0 - uv.y) * height as f64 - 0.5;
+
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = self.texel(x0, y0).lerp(self.texel(x0 + 1, y0), tx);
+        let bottom = self.texel(x0, y0 + 1).lerp(self.texel(x0 + 1, y0 + 1), tx);
+
+        top.lerp(bottom, ty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use glam::{dvec2, DVec2};
+    use image::Rgb;
+
+    use super::*;
+    use crate::material::Lambertian;
+    use crate::vec::approx_eq;
+
+    fn at(uv: DVec2) -> Hit {
+        let material = Arc::new(Lambertian::new(DVec3::ONE));
+        Hit::new(DVec3::ZERO, DVec3::Y, 1.0, true, uv, material)
+    }
+
+    // Black on the left, white on the right
+    fn texture() -> ImageTexture {
+        let mut image = Rgb32FImage::new(2, 2);
+        image.put_pixel(1, 0, Rgb([1.0, 1.0, 1.0]));
+        image.put_pixel(1, 1, Rgb([1.0, 1.0, 1.0]));
+        ImageTexture::new(image)
+    }
+
+    #[test]
+    fn bilinear_between_pixels() {
+        let texture = texture().with_wrap(WrapMode::Clamp);
+
+        assert!(approx_eq(texture.value(&at(dvec2(0.25, 0.5))), DVec3::ZERO));
+        assert!(approx_eq(
+            texture.value(&at(dvec2(0.5, 0.5))),
+            DVec3::splat(0.5)
+        ));
+        assert!(approx_eq(texture.value(&at(dvec2(0.75, 0.5))), DVec3::ONE));
+    }
+
+    #[test]
+    fn wrap_modes() {
+        assert_eq!(WrapMode::Repeat.apply(-1, 4), 3);
+        assert_eq!(WrapMode::Repeat.apply(5, 4), 1);
+        assert_eq!(WrapMode::Mirror.apply(-1, 4), 0);
+        assert_eq!(WrapMode::Mirror.apply(5, 4), 2);
+        assert_eq!(WrapMode::Clamp.apply(-3, 4), 0);
+        assert_eq!(WrapMode::Clamp.apply(9, 4), 3);
+
+        // Repeating blends the right edge back into the left
+        let texture = texture();
+        assert!(approx_eq(
+            texture.value(&at(dvec2(1.0, 0.5))),
+            DVec3::splat(0.5)
+        ));
+    }
+}
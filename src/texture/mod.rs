@@ -0,0 +1,11 @@
+pub mod checker;
+pub mod imagetexture;
+pub mod noise;
+pub mod solid;
+pub mod traits;
+
+pub use checker::Checker;
+pub use imagetexture::{ImageTexture, WrapMode};
+pub use noise::{Noise, NoiseStyle, Perlin};
+pub use solid::SolidColour;
+pub use traits::Texture;
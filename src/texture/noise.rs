@@ -0,0 +1,180 @@
+use std::f64::consts::PI;
+
+use glam::{dvec3, DVec3};
+
+use super::traits::Texture;
+use crate::hittable::Hit;
+
+const POINT_COUNT: usize = 256;
+
+// Gradient noise on a lattice of random unit vectors
+pub struct Perlin {
+    gradients: Vec<DVec3>,
+    permutations: [Vec<usize>; 3],
+}
+
+impl Perlin {
+    // Same seed gives the same noise, which matters for animations
+    pub fn new(seed: u64) -> Self {
+        let mut rng = fastrand::Rng::with_seed(seed);
+
+        let gradients = (0..POINT_COUNT)
+            .map(|_| {
+                let z = 2.0 * rng.f64() - 1.0;
+                let phi = 2.0 * PI * rng.f64();
+                let r = (1.0 - z * z).sqrt();
+                dvec3(r * phi.cos(), r * phi.sin(), z)
+            })
+            .collect();
+
+        let mut permutation = || {
+            let mut out: Vec<usize> = (0..POINT_COUNT).collect();
+            rng.shuffle(&mut out);
+            out
+        };
+
+        Perlin {
+            gradients,
+            permutations: [permutation(), permutation(), permutation()],
+        }
+    }
+
+    fn gradient(&self, i: i64, j: i64, k: i64) -> DVec3 {
+        let [x, y, z] = &self.permutations;
+        let mask = POINT_COUNT as i64 - 1;
+        let index = x[(i & mask) as usize] ^ y[(j & mask) as usize] ^ z[(k & mask) as usize];
+        self.gradients[index]
+    }
+
+    // Roughly in [-1, 1]
+    pub fn noise(&self, point: DVec3) -> f64 {
+        let cell = point.floor();
+        let local = point - cell;
+
+        // Hermite smoothing removes grid artifacts
+        let smooth = local * local * (3.0 - 2.0 * local);
+
+        let (i, j, k) = (cell.x as i64, cell.y as i64, cell.z as i64);
+        let mut out = 0.0;
+
+        for di in 0..2 {
+            for dj in 0..2 {
+                for dk in 0..2 {
+                    let corner = dvec3(di as f64, dj as f64, dk as f64);
+                    let weight =
+                        (corner * smooth + (1.0 - corner) * (1.0 - smooth)).element_product();
+                    let gradient = self.gradient(i + di, j + dj, k + dk);
+
+                    out += weight * gradient.dot(local - corner);
+                }
+            }
+        }
+
+        out
+    }
+
+    // Fractal Brownian motion, each octave has double the frequency and half the amplitude
+    pub fn fbm(&self, point: DVec3, octaves: u32) -> f64 {
+        (0..octaves)
+            .map(|i| 0.5f64.powi(i as i32) * self.noise(point * 2.0f64.powi(i as i32)))
+            .sum()
+    }
+
+    // Like fbm but with absolute values, gives sharp creases
+    pub fn turbulence(&self, point: DVec3, octaves: u32) -> f64 {
+        (0..octaves)
+            .map(|i| 0.5f64.powi(i as i32) * self.noise(point * 2.0f64.powi(i as i32)).abs())
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseStyle {
+    Fbm,
+    Turbulence,
+    // Sine bands along z distorted by turbulence
+    Marble,
+}
+
+// Solid texture, so it depends on the point and not uv
+pub struct Noise {
+    perlin: Perlin,
+    colour: DVec3,
+    scale: f64,
+    octaves: u32,
+    style: NoiseStyle,
+}
+
+impl Noise {
+    pub fn new(scale: f64) -> Self {
+        Noise {
+            perlin: Perlin::new(0),
+            colour: DVec3::ONE,
+            scale,
+            octaves: 7,
+            style: NoiseStyle::Fbm,
+        }
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.perlin = Perlin::new(seed);
+        self
+    }
+
+    pub fn with_colour(mut self, colour: DVec3) -> Self {
+        self.colour = colour;
+        self
+    }
+
+    pub fn with_octaves(mut self, octaves: u32) -> Self {
+        self.octaves = octaves;
+        self
+    }
+
+    pub fn with_style(mut self, style: NoiseStyle) -> Self {
+        self.style = style;
+        self
+    }
+}
+
+impl Texture for Noise {
+    fn value(&self, hit: &Hit) -> DVec3 {
+        let p = hit.point * self.scale;
+
+        let value = match self.style {
+            NoiseStyle::Fbm => 0.5 * (1.0 + self.perlin.fbm(p, self.octaves)),
+            NoiseStyle::Turbulence => self.perlin.turbulence(p, self.octaves),
+            NoiseStyle::Marble => {
+                0.5 * (1.0 + (p.z + 10.0 * self.perlin.turbulence(p, self.octaves)).sin())
+            }
+        };
+
+        self.colour * value.clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zero_on_lattice() {
+        let perlin = Perlin::new(1);
+
+        assert_eq!(perlin.noise(dvec3(3.0, -2.0, 7.0)), 0.0);
+    }
+
+    #[test]
+    fn seeded_and_bounded() {
+        let a = Perlin::new(42);
+        let b = Perlin::new(42);
+
+        for i in 0..100 {
+            let point = dvec3(i as f64 * 0.37, i as f64 * 0.11, -(i as f64) * 0.73);
+            let value = a.noise(point);
+
+            assert_eq!(value, b.noise(point));
+            assert!((-1.0..=1.0).contains(&value));
+        }
+    }
+}
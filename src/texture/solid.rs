@@ -0,0 +1,20 @@
+use glam::DVec3;
+
+use super::traits::Texture;
+use crate::hittable::Hit;
+
+pub struct SolidColour {
+    colour: DVec3,
+}
+
+impl SolidColour {
+    pub fn new(colour: DVec3) -> Self {
+        SolidColour { colour }
+    }
+}
+
+impl Texture for SolidColour {
+    fn value(&self, _hit: &Hit) -> DVec3 {
+        self.colour
+    }
+}
@@ -0,0 +1,8 @@
+use glam::DVec3;
+
+use crate::hittable::Hit;
+
+pub trait Texture: Send + Sync {
+    // Colour at a hit, from its uv, its point or both
+    fn value(&self, hit: &Hit) -> DVec3;
+}
@@ -0,0 +1,244 @@
+use glam::{DMat3, DVec3};
+use image::{Rgb, Rgb32FImage, RgbImage};
+
+// Maps scene referred linear values to display linear values in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMap {
+    // Values above 1 are cut off
+    Clamp,
+    Reinhard,
+    // Values at white map to exactly 1 and anything brighter clips
+    ReinhardExtended { white: f64 },
+    // Narkowicz's fit of the ACES filmic curve
+    Aces,
+    // John Hable's Uncharted 2 curve
+    Hable,
+    // Troy Sobotka's AgX, using the polynomial fit by Benjamin Wrensch
+    Agx,
+}
+
+// Applied after tone mapping to encode for display
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transfer {
+    // Plain power law using the gamma from CameraParams
+    Gamma,
+    // Piecewise IEC 61966-2-1 curve
+    Srgb,
+    Linear,
+}
+
+fn hable_partial(x: DVec3) -> DVec3 {
+    const A: f64 = 0.15;
+    const B: f64 = 0.50;
+    const C: f64 = 0.10;
+    const D: f64 = 0.20;
+    const E: f64 = 0.02;
+    const F: f64 = 0.30;
+
+    (x * (A * x + C * B) + D * E) / (x * (A * x + B) + D * F) - E / F
+}
+
+fn agx(colour: DVec3) -> DVec3 {
+    // Column major, converts into and out of the AgX working space
+    const INSET: DMat3 = DMat3::from_cols_array(&[
+        0.842479062253094,
+        0.0423282422610123,
+        0.0423756549057051,
+        0.0784335999999992,
+        0.878468636469772,
+        0.0784336,
+        0.0792237451477643,
+        0.0791661274605434,
+        0.879142973793104,
+    ]);
+    const OUTSET: DMat3 = DMat3::from_cols_array(&[
+        1.19687900512017,
+        -0.0528968517574562,
+        -0.0529716355144438,
+        -0.0980208811401368,
+        1.15190312990417,
+        -0.0980434501171241,
+        -0.0990297440797205,
+        -0.0989611768448433,
+        1.15107367264116,
+    ]);
+
+    // Range of exposure values in stops around middle grey
+    const MIN_EV: f64 = -12.47393;
+    const MAX_EV: f64 = 4.026069;
+
+    let x = (INSET * colour)
+        .max(DVec3::splat(1e-10))
+        .map(f64::log2)
+        .clamp(DVec3::splat(MIN_EV), DVec3::splat(MAX_EV));
+    let x = (x - MIN_EV) / (MAX_EV - MIN_EV);
+
+    let x2 = x * x;
+    let x4 = x2 * x2;
+    let curve =
+        15.5 * x4 * x2 - 40.14 * x4 * x + 31.96 * x4 - 6.868 * x2 * x + 0.4298 * x2 + 0.1191 * x
+            - 0.00232;
+
+    // The curve outputs display encoded values so undo the 2.2 gamma
+    (OUTSET * curve).max(DVec3::ZERO).powf(2.2)
+}
+
+impl ToneMap {
+    pub fn apply(&self, colour: DVec3) -> DVec3 {
+        let colour = colour.max(DVec3::ZERO);
+
+        match *self {
+            ToneMap::Clamp => colour.min(DVec3::ONE),
+            ToneMap::Reinhard => colour / (1.0 + colour),
+            ToneMap::ReinhardExtended { white } => {
+                (colour * (1.0 + colour / (white * white)) / (1.0 + colour)).min(DVec3::ONE)
+            }
+            ToneMap::Aces => {
+                let numerator = colour * (2.51 * colour + 0.03);
+                let denominator = colour * (2.43 * colour + 0.59) + 0.14;
+                (numerator / denominator).clamp(DVec3::ZERO, DVec3::ONE)
+            }
+            ToneMap::Hable => {
+                // Exposure bias and linear white point from the original talk
+                let white = hable_partial(DVec3::splat(11.2));
+                (hable_partial(2.0 * colour) / white).min(DVec3::ONE)
+            }
+            ToneMap::Agx => agx(colour),
+        }
+    }
+}
+
+impl Transfer {
+    pub fn encode(&self, data: f64, gamma: f64) -> f64 {
+        if data <= 0.0 {
+            return 0.0;
+        }
+
+        match self {
+            Transfer::Gamma => data.powf(1.0 / gamma),
+            Transfer::Srgb => {
+                if data <= 0.0031308 {
+                    12.92 * data
+                } else {
+                    1.055 * data.powf(1.0 / 2.4) - 0.055
+                }
+            }
+            Transfer::Linear => data,
+        }
+    }
+}
+
+// Everything between a linear render and an 8 bit image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayTransform {
+    pub tone_map: ToneMap,
+    // In stops, each one doubles the brightness
+    pub exposure: f64,
+    pub transfer: Transfer,
+    pub gamma: f64,
+}
+
+impl DisplayTransform {
+    pub fn apply(&self, colour: DVec3) -> DVec3 {
+        let exposed = colour * self.exposure.exp2();
+
+        self.tone_map
+            .apply(exposed)
+            .map(|x| self.transfer.encode(x, self.gamma))
+    }
+
+    pub fn to_rgb(&self, data: &Rgb<f32>) -> Rgb<u8> {
+        let colour = DVec3::from_array(data.0.map(|x| x as f64));
+
+        Rgb(self
+            .apply(colour)
+            .to_array()
+            .map(|x| (x.clamp(0.0, 0.999) * 256.0).floor() as u8))
+    }
+
+    pub fn develop(&self, image: &Rgb32FImage) -> RgbImage {
+        RgbImage::from_par_fn(image.width(), image.height(), |x, y| {
+            self.to_rgb(image.get_pixel(x, y))
+        })
+    }
+}
+
+impl Default for DisplayTransform {
+    fn default() -> Self {
+        Self {
+            tone_map: ToneMap::Clamp,
+            exposure: 0.0,
+            transfer: Transfer::Gamma,
+            gamma: 2.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::vec::approx_eq;
+
+    const OPERATORS: [ToneMap; 6] = [
+        ToneMap::Clamp,
+        ToneMap::Reinhard,
+        ToneMap::ReinhardExtended { white: 4.0 },
+        ToneMap::Aces,
+        ToneMap::Hable,
+        ToneMap::Agx,
+    ];
+
+    #[test]
+    fn operators_stay_in_range() {
+        for operator in OPERATORS {
+            let mut previous = -1.0;
+
+            for i in 0..100 {
+                let x = (i as f64 * 0.2 - 10.0).exp2();
+                let y = operator.apply(DVec3::splat(x));
+
+                assert!(
+                    y.cmpge(DVec3::ZERO).all() && y.cmple(DVec3::splat(1.0001)).all(),
+                    "{operator:?} out of range at {x}: {y}"
+                );
+                assert!(y.x >= previous - 1e-9, "{operator:?} not monotonic at {x}");
+                previous = y.x;
+            }
+        }
+    }
+
+    #[test]
+    fn reinhard_extended_white_point() {
+        let white = ToneMap::ReinhardExtended { white: 4.0 }.apply(DVec3::splat(4.0));
+        assert!(approx_eq(white, DVec3::ONE));
+    }
+
+    #[test]
+    fn srgb_transfer() {
+        assert_eq!(Transfer::Srgb.encode(0.0, 2.2), 0.0);
+        assert!((Transfer::Srgb.encode(1.0, 2.2) - 1.0).abs() < 1e-9);
+        assert!((Transfer::Srgb.encode(0.001, 2.2) - 0.01292).abs() < 1e-9);
+        assert!((Transfer::Srgb.encode(0.5, 2.2) - 0.735357).abs() < 1e-6);
+    }
+
+    #[test]
+    fn exposure_in_stops() {
+        let display = DisplayTransform {
+            tone_map: ToneMap::Clamp,
+            exposure: 1.0,
+            transfer: Transfer::Linear,
+            gamma: 2.0,
+        };
+
+        assert!(approx_eq(
+            display.apply(DVec3::splat(0.25)),
+            DVec3::splat(0.5)
+        ));
+    }
+
+    #[test]
+    fn default_matches_gamma_two() {
+        let pixel = DisplayTransform::default().to_rgb(&Rgb([0.25, 1.0, 2.0]));
+        assert_eq!(pixel, Rgb([128, 255, 255]));
+    }
+}
@@ -19,6 +19,11 @@ pub fn approx_eq(a: DVec3, b: DVec3) -> bool {
     approx_eq_with_error(a, b, ERROR)
 }
 
+// Perceived brightness of a linear Rec. 709 colour
+pub fn luminance(colour: DVec3) -> f64 {
+    colour.dot(DVec3::new(0.2126, 0.7152, 0.0722))
+}
+
 #[cfg(test)]
 mod tests {
     use core::f64;
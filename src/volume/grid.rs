@@ -0,0 +1,205 @@
+use std::sync::Arc;
+
+use glam::DVec3;
+
+use super::traits::Volume;
+use crate::hittable::Aabb;
+use crate::material::Material;
+use crate::random::random_exponential;
+use crate::ray::Ray;
+
+// Dense 3D grid of densities, x varies fastest then y then z
+pub struct DensityGrid {
+    sizes: [usize; 3],
+    data: Vec<f32>,
+    max: f64,
+}
+
+impl DensityGrid {
+    pub fn new(sizes: [usize; 3], data: Vec<f32>) -> Self {
+        assert!(sizes.iter().all(|&x| x > 0));
+        assert_eq!(data.len(), sizes.iter().product::<usize>());
+
+        let max = data.iter().fold(0.0f32, |acc, &x| acc.max(x)) as f64;
+        DensityGrid { sizes, data, max }
+    }
+
+    pub fn sizes(&self) -> [usize; 3] {
+        self.sizes
+    }
+
+    // Interpolation never exceeds the largest voxel, so this is a majorant
+    pub fn max(&self) -> f64 {
+        self.max
+    }
+
+    fn voxel(&self, x: usize, y: usize, z: usize) -> f64 {
+        let [width, height, _] = self.sizes;
+        self.data[x + width * (y + height * z)].max(0.0) as f64
+    }
+
+    // Trilinear interpolation of voxel centres, uvw covers the grid with
+    // [0, 1]^3 and the edges are clamped
+    pub fn sample(&self, uvw: DVec3) -> f64 {
+        let size = DVec3::new(
+            self.sizes[0] as f64,
+            self.sizes[1] as f64,
+            self.sizes[2] as f64,
+        );
+        let p = (uvw * size - 0.5).clamp(DVec3::ZERO, size - 1.0);
+
+        let base = p.floor();
+        let t = p - base;
+        let [x0, y0, z0] = base.to_array().map(|x| x as usize);
+        let x1 = (x0 + 1).min(self.sizes[0] - 1);
+        let y1 = (y0 + 1).min(self.sizes[1] - 1);
+        let z1 = (z0 + 1).min(self.sizes[2] - 1);
+
+        let lerp = |a: f64, b: f64, t: f64| a + (b - a) * t;
+        let plane = |z| {
+            lerp(
+                lerp(self.voxel(x0, y0, z), self.voxel(x1, y0, z), t.x),
+                lerp(self.voxel(x0, y1, z), self.voxel(x1, y1, z), t.x),
+                t.y,
+            )
+        };
+
+        lerp(plane(z0), plane(z1), t.z)
+    }
+}
+
+// Smoke or clouds from a density grid stretched over an axis aligned box
+pub struct GridVolume {
+    grid: DensityGrid,
+    bounds: Aabb,
+    // Multiplies grid values
+    density: f64,
+    phase: Arc<dyn Material>,
+}
+
+impl GridVolume {
+    // Use Isotropic or HenyeyGreenstein for phase
+    pub fn new(grid: DensityGrid, bounds: Aabb, phase: Arc<dyn Material>) -> Self {
+        GridVolume {
+            grid,
+            bounds,
+            density: 1.0,
+            phase,
+        }
+    }
+
+    pub fn with_density(mut self, density: f64) -> Self {
+        self.density = density;
+        self
+    }
+
+    fn density_at(&self, point: DVec3) -> f64 {
+        let uvw = (point - self.bounds.min) / self.bounds.extent();
+        self.grid.sample(uvw) * self.density
+    }
+
+    fn majorant(&self) -> f64 {
+        self.grid.max() * self.density
+    }
+}
+
+impl Volume for GridVolume {
+    // Delta tracking: take steps through a medium made homogeneous with
+    // fictitious particles and only scatter off the real ones
+    fn sample_distance(&self, ray: &Ray, min: f64, max: f64) -> Option<f64> {
+        let majorant = self.majorant();
+
+        if majorant <= 0.0 {
+            return None;
+        }
+
+        let (mut distance, end) = self.bounds.interval(ray, min, max)?;
+
+        loop {
+            distance += random_exponential(majorant);
+
+            if distance >= end {
+                return None;
+            }
+
+            if fastrand::f64() * majorant < self.density_at(ray.at(distance)) {
+                return Some(distance);
+            }
+        }
+    }
+
+    // Ratio tracking: the same steps, weighted by the chance of each being
+    // fictitious rather than stopping at the first real one
+    fn transmittance(&self, ray: &Ray, min: f64, max: f64) -> f64 {
+        let majorant = self.majorant();
+
+        let Some((mut distance, end)) = self.bounds.interval(ray, min, max) else {
+            return 1.0;
+        };
+
+        if majorant <= 0.0 {
+            return 1.0;
+        }
+
+        let mut transmittance = 1.0;
+
+        loop {
+            distance += random_exponential(majorant);
+
+            if distance >= end {
+                return transmittance;
+            }
+
+            transmittance *= 1.0 - self.density_at(ray.at(distance)) / majorant;
+        }
+    }
+
+    fn phase(&self) -> Arc<dyn Material> {
+        self.phase.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use glam::dvec3;
+
+    use super::*;
+    use crate::material::Isotropic;
+
+    #[test]
+    fn trilinear_between_centres() {
+        let grid = DensityGrid::new([2, 1, 1], vec![1.0, 3.0]);
+
+        assert_eq!(grid.sample(dvec3(0.25, 0.5, 0.5)), 1.0);
+        assert_eq!(grid.sample(dvec3(0.5, 0.5, 0.5)), 2.0);
+        assert_eq!(grid.sample(dvec3(1.0, 0.5, 0.5)), 3.0);
+        assert_eq!(grid.max(), 3.0);
+    }
+
+    #[test]
+    fn ratio_tracking_matches_beer_lambert() {
+        // Ramps from 0.5 to 1.5 across the middle, optical depth 2 along x
+        let grid = DensityGrid::new([2, 1, 1], vec![0.5, 1.5]);
+        let volume = GridVolume::new(
+            grid,
+            Aabb::new(dvec3(-1.0, -1.0, -1.0), dvec3(1.0, 1.0, 1.0)),
+            Arc::new(Isotropic::new(DVec3::ONE)),
+        );
+
+        let ray = Ray::new(dvec3(-5.0, 0.0, 0.0), DVec3::X);
+        let n = 20_000;
+
+        let mean = (0..n)
+            .map(|_| volume.transmittance(&ray, 1e-9, f64::MAX))
+            .sum::<f64>()
+            / n as f64;
+        let passed = (0..n)
+            .filter(|_| volume.sample_distance(&ray, 1e-9, f64::MAX).is_none())
+            .count() as f64
+            / n as f64;
+
+        let expected = (-2.0f64).exp();
+        assert!((mean - expected).abs() < 0.01);
+        assert!((passed - expected).abs() < 0.02);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod grid;
+pub mod traits;
+
+pub use grid::{DensityGrid, GridVolume};
+pub use traits::Volume;
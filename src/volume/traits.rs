@@ -0,0 +1,19 @@
+use std::sync::Arc;
+
+use crate::material::Material;
+use crate::ray::Ray;
+
+// Participating medium handled by the scene rather than as a Hittable, so
+// shadow rays can be attenuated smoothly instead of randomly blocked
+pub trait Volume: Send + Sync {
+    // Distance of the next scattering event between min and max, None if the
+    // ray passes through
+    fn sample_distance(&self, ray: &Ray, min: f64, max: f64) -> Option<f64>;
+
+    // Fraction of light passing through between min and max, may be a
+    // random estimate
+    fn transmittance(&self, ray: &Ray, min: f64, max: f64) -> f64;
+
+    // Material scattering events use
+    fn phase(&self) -> Arc<dyn Material>;
+}